@@ -1,18 +1,13 @@
-fn main() {
-    let mut fishes = lanternfish::parse_stdin();
+use std::error::Error;
 
-    for _ in 0..80 {
-        let mut spawn = 0;
-        for f in &mut fishes {
-            if *f == 0 {
-                *f = 6;
-                spawn += 1;
-            } else {
-                *f -= 1;
-            }
-        }
-        fishes.resize(fishes.len() + spawn, 8);
-    }
+use lanternfish::lifecycle::{LifeCycle, Population};
 
-    println!("fishes: {}", fishes.len());
+fn main() -> Result<(),Box<dyn Error>> {
+    let fishes = lanternfish::parse_stdin();
+
+    let mut population = Population::new(LifeCycle::puzzle(), &fishes)?;
+
+    println!("fishes: {}", population.run(80));
+
+    Ok(())
 }
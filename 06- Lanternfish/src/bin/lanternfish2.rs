@@ -1,34 +1,13 @@
-use std::collections::HashMap;
+use std::error::Error;
 
-type FishMap = HashMap<u8, u64>;
+use lanternfish::lifecycle::{LifeCycle, Population};
 
-fn distribute(fishlist: lanternfish::FishList) -> FishMap {
-    let mut fishmap: HashMap<u8, u64> = HashMap::new();
-    for f in &fishlist {
-        fishmap.insert(*f, fishmap.get(f).unwrap_or(&0) + 1);
-    }
-    fishmap
-}
+fn main() -> Result<(),Box<dyn Error>> {
+    let fishes = lanternfish::parse_stdin();
 
-fn main() {
-    let mut fishes = distribute(lanternfish::parse_stdin());
+    let mut population = Population::new(LifeCycle::puzzle(), &fishes)?;
 
-    let mut total: u64 = 0;
-    for _ in 0..256 {
-        let mut next_fishes: FishMap = FishMap::new();
-        total = 0;
-        for (age, count) in &fishes {
-            if *age == 0 {
-                next_fishes.insert(6, *count + next_fishes.get(&6).unwrap_or(&0));
-                next_fishes.insert(8, *count);
-                total += count;
-            } else {
-                next_fishes.insert(*age - 1, *count + next_fishes.get(&(*age - 1)).unwrap_or(&0));
-            }
-            total += count;
-        }
-        fishes = next_fishes;
-    }
+    println!("fishes: {}", population.run(256));
 
-    println!("fishes: {}", total);
+    Ok(())
 }
@@ -0,0 +1,13 @@
+use std::error::Error;
+use std::io;
+
+use lanternfish::lifecycle::{LifeCycle, Population};
+
+fn main() -> Result<(),Box<dyn Error>> {
+    let fishes = lanternfish::parse_stdin();
+
+    let mut population = Population::new(LifeCycle::puzzle(), &fishes)?;
+    population.write_csv(256, &mut io::stdout().lock())?;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::error::Error;
+use std::thread;
+
+use lanternfish::lifecycle::{LifeCycle, Population};
+use lanternfish::montecarlo::{Dist, StochasticLifeCycle, Z95};
+
+const DAYS: usize = 80;
+const TRIALS: usize = 1000;
+const SEED: u64 = 2021;
+
+fn main() -> Result<(),Box<dyn Error>> {
+    let fishes = lanternfish::parse_stdin();
+
+    let deterministic = Population::new(LifeCycle::puzzle(), &fishes)?.run(DAYS);
+    println!("deterministic fishes: {}", deterministic);
+
+    // Spawn every 6 to 8 days, with 0 to 2 newborns per spawn.
+    let lifecycle = StochasticLifeCycle {
+        cycle: Dist::uniform(6, 8)?,
+        litter: Dist::weighted(&[(0, 1.0), (1, 2.0), (2, 1.0)])?,
+        ..StochasticLifeCycle::try_from(LifeCycle::puzzle())?
+    };
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let summary = lifecycle.monte_carlo(&fishes, DAYS, TRIALS, SEED, threads).ok_or("no trials")?;
+
+    let (low, high) = summary.confidence_interval(Z95);
+    println!("stochastic fishes: mean {:.1}, 95% CI [{:.1}, {:.1}]", summary.mean(), low, high);
+    for p in [5.0, 25.0, 50.0, 75.0, 95.0] {
+        println!("  p{}: {}", p, summary.percentile(p));
+    }
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use std::io::{self, BufRead};
 
+pub mod lifecycle;
+pub mod montecarlo;
+
 pub type FishList = Vec<u8>;
 
 pub fn parse_stdin() -> FishList {
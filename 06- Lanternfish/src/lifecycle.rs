@@ -0,0 +1,213 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::FishList;
+
+/// Parameters of the lanternfish life cycle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LifeCycle {
+    /// Number of days between two spawns of an adult fish.
+    pub cycle: usize,
+    /// Extra days before a newborn fish spawns for the first time.
+    pub maturation: usize,
+    /// Number of newborns per spawn.
+    pub litter: u64,
+    /// Fraction of the population that dies each day, if any.
+    pub mortality: Option<f64>,
+}
+
+impl LifeCycle {
+    /// The life cycle from the puzzle: spawn every 7 days, newborns take 2 more days to mature.
+    pub fn puzzle() -> Self {
+        LifeCycle {
+            cycle: 7,
+            maturation: 2,
+            litter: 1,
+            mortality: None,
+        }
+    }
+
+    /// Number of distinct timer values a fish can have.
+    pub fn buckets(&self) -> usize {
+        self.cycle + self.maturation
+    }
+}
+
+impl Default for LifeCycle {
+    fn default() -> Self {
+        Self::puzzle()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LifeCycleError {
+    /// The spawn cycle is 0 days.
+    Cycle,
+    /// The mortality isn't within [0,1].
+    Mortality(f64),
+    /// A fish has a timer too high for the life cycle.
+    Timer(u8),
+}
+
+impl fmt::Display for LifeCycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Cycle => write!(f, "spawn cycle must be at least 1 day"),
+            Self::Mortality(m) => write!(f, "mortality must be within [0,1]: {}", m),
+            Self::Timer(t) => write!(f, "timer {} out of range for this life cycle", t),
+        }
+    }
+}
+
+impl Error for LifeCycleError {}
+
+/// A population of fishes, counted by timer value.
+#[derive(Clone, Debug)]
+pub struct Population {
+    lifecycle: LifeCycle,
+    buckets: Vec<u64>,
+    /// Expected deaths not accounted for yet in each bucket, as a fraction of a fish.
+    dying: Vec<f64>,
+    day: usize,
+}
+
+impl Population {
+    pub fn new(lifecycle: LifeCycle, fishes: &FishList) -> Result<Self, LifeCycleError> {
+        if lifecycle.cycle == 0 {
+            return Err(LifeCycleError::Cycle);
+        }
+        if let Some(mortality) = lifecycle.mortality {
+            if !(0.0..=1.0).contains(&mortality) {
+                return Err(LifeCycleError::Mortality(mortality));
+            }
+        }
+
+        let mut buckets = vec![0; lifecycle.buckets()];
+        for &f in fishes {
+            *buckets.get_mut(f as usize).ok_or(LifeCycleError::Timer(f))? += 1;
+        }
+        let dying = vec![0.0; buckets.len()];
+
+        Ok(Population { lifecycle, buckets, dying, day: 0 })
+    }
+
+    pub fn lifecycle(&self) -> &LifeCycle { &self.lifecycle }
+    pub fn buckets(&self) -> &[u64] { &self.buckets }
+    pub fn day(&self) -> usize { self.day }
+
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Advance the population by one day.
+    pub fn step(&mut self) {
+        // Fishes at timer 0 spawn: they restart their cycle, and their newborns start at the top.
+        let spawning = self.buckets[0];
+        self.buckets.rotate_left(1);
+        let last = self.buckets.len() - 1;
+        self.buckets[last] = spawning * self.lifecycle.litter;
+        self.buckets[self.lifecycle.cycle - 1] += spawning;
+
+        // Deaths are applied to the expected value. Whole fishes die, and the fraction left over
+        // follows the fishes to their next timer, so that even small groups die out eventually.
+        if let Some(mortality) = self.lifecycle.mortality {
+            let parents = self.dying[0];
+            self.dying.rotate_left(1);
+            self.dying[last] = 0.0;
+            self.dying[self.lifecycle.cycle - 1] += parents;
+
+            for (b, dying) in self.buckets.iter_mut().zip(&mut self.dying) {
+                let expected = *b as f64 * mortality + *dying;
+                let dead = (expected.floor() as u64).min(*b);
+                *b -= dead;
+                *dying = if *b > 0 { expected - dead as f64 } else { 0.0 };
+            }
+        }
+
+        self.day += 1;
+    }
+
+    /// Advance the population by several days.
+    pub fn run(&mut self, days: usize) -> u64 {
+        for _ in 0..days {
+            self.step();
+        }
+        self.total()
+    }
+
+    /// Advance the population by several days, writing the timer buckets of each day (including
+    /// the current one) as CSV.
+    pub fn write_csv<W: Write>(&mut self, days: usize, out: &mut W) -> io::Result<()> {
+        let header: Vec<String> = (0..self.buckets.len()).map(|b| format!("timer{}", b)).collect();
+        writeln!(out, "day,{},total", header.join(","))?;
+        self.write_csv_row(out)?;
+        for _ in 0..days {
+            self.step();
+            self.write_csv_row(out)?;
+        }
+        Ok(())
+    }
+
+    fn write_csv_row<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let row: Vec<String> = self.buckets.iter().map(|c| c.to_string()).collect();
+        writeln!(out, "{},{},{}", self.day, row.join(","), self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzle() {
+        let fishes = vec![3,4,3,1,2];
+        let mut population = Population::new(LifeCycle::puzzle(), &fishes).unwrap();
+        assert_eq!(population.run(18), 26);
+        assert_eq!(population.run(80-18), 5934);
+        assert_eq!(population.run(256-80), 26984457539);
+    }
+
+    #[test]
+    fn litter() {
+        let lifecycle = LifeCycle { litter: 2, ..LifeCycle::puzzle() };
+        let mut population = Population::new(lifecycle, &vec![0]).unwrap();
+        population.step();
+        assert_eq!(population.buckets(), &[0,0,0,0,0,0,1,0,2]);
+    }
+
+    #[test]
+    fn mortality() {
+        let lifecycle = LifeCycle { mortality: Some(0.5), ..LifeCycle::puzzle() };
+        let mut population = Population::new(lifecycle, &vec![5; 8]).unwrap();
+        population.step();
+        assert_eq!(population.buckets()[4], 4);
+
+        // A single fish still dies on average after 1/mortality days.
+        let lifecycle = LifeCycle { mortality: Some(0.25), ..LifeCycle::puzzle() };
+        let mut population = Population::new(lifecycle, &vec![8]).unwrap();
+        assert_eq!(population.run(3), 1);
+        assert_eq!(population.run(1), 0);
+    }
+
+    #[test]
+    fn invalid() {
+        let fishes = vec![3];
+        let lifecycle = LifeCycle { cycle: 0, ..LifeCycle::puzzle() };
+        assert_eq!(Population::new(lifecycle, &fishes).err(), Some(LifeCycleError::Cycle));
+        let lifecycle = LifeCycle { mortality: Some(1.5), ..LifeCycle::puzzle() };
+        assert_eq!(Population::new(lifecycle, &fishes).err(), Some(LifeCycleError::Mortality(1.5)));
+        assert_eq!(Population::new(LifeCycle::puzzle(), &vec![9]).err(), Some(LifeCycleError::Timer(9)));
+    }
+
+    #[test]
+    fn csv() {
+        let mut population = Population::new(LifeCycle::puzzle(), &vec![0,1]).unwrap();
+        let mut out = Vec::new();
+        population.write_csv(1, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+            day,timer0,timer1,timer2,timer3,timer4,timer5,timer6,timer7,timer8,total\n\
+            0,1,1,0,0,0,0,0,0,0,2\n\
+            1,1,0,0,0,0,0,1,0,1,3\n");
+    }
+}
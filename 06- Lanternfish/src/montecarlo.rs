@@ -0,0 +1,262 @@
+use std::error::Error;
+use std::fmt;
+use std::thread;
+
+use rand::distributions::{Bernoulli, Distribution, WeightedError, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::lifecycle::LifeCycle;
+use crate::FishList;
+
+/// z-score for a 95% confidence interval.
+pub const Z95: f64 = 1.96;
+
+/// A distribution over non-negative integers, checked when it is built.
+#[derive(Clone, Debug)]
+pub struct Dist(Kind);
+
+#[derive(Clone, Debug)]
+enum Kind {
+    Constant(u64),
+    Uniform(u64, u64),
+    Weighted(Vec<u64>, WeightedIndex<f64>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DistError {
+    /// The range of a uniform distribution is empty.
+    EmptyRange { min: u64, max: u64 },
+    /// A weighted distribution has no values.
+    NoValues,
+    /// A weight is negative or not a number, or the weights add up to 0.
+    Weights,
+    /// A probability isn't within [0,1].
+    Probability(f64),
+}
+
+impl fmt::Display for DistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyRange { min, max } => write!(f, "empty range: {}..={}", min, max),
+            Self::NoValues => write!(f, "no values to choose from"),
+            Self::Weights => write!(f, "weights must be non-negative, with a positive total"),
+            Self::Probability(p) => write!(f, "probability must be within [0,1]: {}", p),
+        }
+    }
+}
+
+impl Error for DistError {}
+
+impl Dist {
+    pub fn constant(n: u64) -> Self {
+        Dist(Kind::Constant(n))
+    }
+
+    /// Uniform over an inclusive range.
+    pub fn uniform(min: u64, max: u64) -> Result<Self, DistError> {
+        if min > max {
+            return Err(DistError::EmptyRange { min, max });
+        }
+        Ok(Dist(Kind::Uniform(min, max)))
+    }
+
+    /// Each value with the given relative weight.
+    pub fn weighted(values: &[(u64, f64)]) -> Result<Self, DistError> {
+        let index = WeightedIndex::new(values.iter().map(|(_, w)| *w)).map_err(|e| match e {
+            WeightedError::NoItem => DistError::NoValues,
+            _ => DistError::Weights,
+        })?;
+        Ok(Dist(Kind::Weighted(values.iter().map(|(v, _)| *v).collect(), index)))
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match &self.0 {
+            Kind::Constant(n) => *n,
+            Kind::Uniform(min, max) => rng.gen_range(*min..=*max),
+            Kind::Weighted(values, index) => values[index.sample(rng)],
+        }
+    }
+
+    fn max(&self) -> u64 {
+        match &self.0 {
+            Kind::Constant(n) => *n,
+            Kind::Uniform(_, max) => *max,
+            Kind::Weighted(values, _) => values.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+/// Parameters of a randomized lanternfish life cycle. Each fish draws its own timers and litter
+/// size every time it spawns.
+#[derive(Clone, Debug)]
+pub struct StochasticLifeCycle {
+    /// Number of days between two spawns of an adult fish.
+    pub cycle: Dist,
+    /// Extra days before a newborn fish spawns for the first time.
+    pub maturation: Dist,
+    /// Number of newborns per spawn.
+    pub litter: Dist,
+    /// Probability for each fish to die on a given day, if any, as built by [`mortality`].
+    pub mortality: Option<Bernoulli>,
+}
+
+/// Chance for each fish to die on a given day.
+pub fn mortality(p: f64) -> Result<Bernoulli, DistError> {
+    Bernoulli::new(p).map_err(|_| DistError::Probability(p))
+}
+
+impl TryFrom<LifeCycle> for StochasticLifeCycle {
+    type Error = DistError;
+
+    fn try_from(lifecycle: LifeCycle) -> Result<Self, DistError> {
+        Ok(StochasticLifeCycle {
+            cycle: Dist::constant(lifecycle.cycle as u64),
+            maturation: Dist::constant(lifecycle.maturation as u64),
+            litter: Dist::constant(lifecycle.litter),
+            mortality: lifecycle.mortality.map(mortality).transpose()?,
+        })
+    }
+}
+
+impl StochasticLifeCycle {
+    /// Simulate a single trial. The cost is linear in the size of the population, so this is only
+    /// practical for a limited number of days.
+    pub fn simulate<R: Rng>(&self, fishes: &FishList, days: usize, rng: &mut R) -> u64 {
+        let size = (self.cycle.max().max(1) + self.maturation.max()) as usize;
+        let mut buckets = vec![0u64; size.max(1 + *fishes.iter().max().unwrap_or(&0) as usize)];
+        for &f in fishes {
+            buckets[f as usize] += 1;
+        }
+
+        for _ in 0..days {
+            let spawning = buckets[0];
+            buckets.rotate_left(1);
+            let last = buckets.len() - 1;
+            buckets[last] = 0;
+
+            for _ in 0..spawning {
+                let cycle = self.cycle.sample(rng).max(1) as usize;
+                buckets[cycle - 1] += 1;
+                // Each newborn draws its own cycle and maturation.
+                for _ in 0..self.litter.sample(rng) {
+                    let first = self.cycle.sample(rng).max(1) + self.maturation.sample(rng);
+                    buckets[first as usize - 1] += 1;
+                }
+            }
+
+            if let Some(death) = self.mortality {
+                for b in &mut buckets {
+                    *b -= (0..*b).filter(|_| death.sample(rng)).count() as u64;
+                }
+            }
+        }
+
+        buckets.iter().sum()
+    }
+
+    /// Run `trials` independent simulations over `threads` threads. Each trial is seeded from
+    /// `seed` and its index, so the results don't depend on the number of threads.
+    /// Returns `None` if there are no trials.
+    pub fn monte_carlo(&self, fishes: &FishList, days: usize, trials: usize, seed: u64, threads: usize) -> Option<Summary> {
+        if trials == 0 {
+            return None;
+        }
+        let threads = threads.clamp(1, trials.max(1));
+        let mut populations: Vec<u64> = thread::scope(|s| {
+            let workers: Vec<_> = (0..threads).map(|t| s.spawn(move || {
+                (t..trials).step_by(threads).map(|trial| {
+                    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(trial as u64));
+                    self.simulate(fishes, days, &mut rng)
+                }).collect::<Vec<_>>()
+            })).collect();
+            workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+        });
+        populations.sort_unstable();
+
+        Some(Summary { populations })
+    }
+}
+
+/// Statistics over the final populations of at least one trial.
+#[derive(Clone, Debug)]
+pub struct Summary {
+    /// Final populations, sorted.
+    populations: Vec<u64>,
+}
+
+impl Summary {
+    pub fn trials(&self) -> usize {
+        self.populations.len()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.populations.iter().map(|&p| p as f64).sum::<f64>() / self.trials() as f64
+    }
+
+    /// Sample standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        let n = self.trials() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let var = self.populations.iter().map(|&p| (p as f64 - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        var.sqrt()
+    }
+
+    /// Nearest-rank percentile, for `p` in [0,100].
+    pub fn percentile(&self, p: f64) -> u64 {
+        assert!((0.0..=100.0).contains(&p));
+        let rank = (p / 100.0 * self.trials() as f64).ceil() as usize;
+        self.populations[rank.saturating_sub(1).min(self.trials() - 1)]
+    }
+
+    /// Confidence interval of the mean for the given z-score (e.g. [`Z95`]).
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let mean = self.mean();
+        let margin = z * self.std_dev() / (self.trials() as f64).sqrt();
+        (mean - margin, mean + margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifecycle::Population;
+
+    #[test]
+    fn deterministic() {
+        let fishes = vec![3,4,3,1,2];
+        let lifecycle = StochasticLifeCycle::try_from(LifeCycle::puzzle()).unwrap();
+        let summary = lifecycle.monte_carlo(&fishes, 80, 4, 0, 2).unwrap();
+        let expected = Population::new(LifeCycle::puzzle(), &fishes).unwrap().run(80);
+        assert_eq!(summary.percentile(0.0), expected);
+        assert_eq!(summary.percentile(100.0), expected);
+        assert_eq!(summary.confidence_interval(Z95), (expected as f64, expected as f64));
+    }
+
+    #[test]
+    fn reproducible() {
+        let fishes = vec![3,4,3,1,2];
+        let lifecycle = StochasticLifeCycle {
+            cycle: Dist::uniform(6, 8).unwrap(),
+            litter: Dist::weighted(&[(0, 1.0), (1, 2.0), (2, 1.0)]).unwrap(),
+            ..StochasticLifeCycle::try_from(LifeCycle::puzzle()).unwrap()
+        };
+        let a = lifecycle.monte_carlo(&fishes, 40, 16, 42, 1).unwrap();
+        let b = lifecycle.monte_carlo(&fishes, 40, 16, 42, 3).unwrap();
+        assert_eq!(a.populations, b.populations);
+        assert!(lifecycle.monte_carlo(&fishes, 40, 0, 42, 3).is_none());
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Dist::uniform(8, 6).err(), Some(DistError::EmptyRange { min: 8, max: 6 }));
+        assert_eq!(Dist::weighted(&[]).err(), Some(DistError::NoValues));
+        assert_eq!(Dist::weighted(&[(1, 0.0), (2, 0.0)]).err(), Some(DistError::Weights));
+        assert_eq!(Dist::weighted(&[(1, -1.0), (2, 2.0)]).err(), Some(DistError::Weights));
+        let lifecycle = LifeCycle { mortality: Some(-0.1), ..LifeCycle::puzzle() };
+        assert_eq!(StochasticLifeCycle::try_from(lifecycle).err(), Some(DistError::Probability(-0.1)));
+    }
+}
@@ -0,0 +1,13 @@
+use treachery_of_whales::plane::{optimize_euclidean, optimize_manhattan, parse_stdin_2d};
+
+fn main() {
+    let crabs = parse_stdin_2d();
+    println!("crabs: {}", crabs.len());
+    println!();
+
+    let optimum = optimize_manhattan(&crabs).unwrap();
+    println!("align(manhattan): {} @ {},{}", optimum.cost, optimum.position.0, optimum.position.1);
+
+    let optimum = optimize_euclidean(&crabs).unwrap();
+    println!("align(euclidean): {:.3} @ {},{}", optimum.cost, optimum.position.0, optimum.position.1);
+}
@@ -0,0 +1,13 @@
+use std::error::Error;
+use std::io;
+
+use treachery_of_whales::parse_stdin;
+use treachery_of_whales::curve::CostCurve;
+
+fn main() -> Result<(),Box<dyn Error>> {
+    let crabs = parse_stdin();
+
+    CostCurve::compute(&crabs).write_csv(&mut io::stdout().lock())?;
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use crate::CrabList;
+
+/// How the total alignment cost behaves, which determines how the optimum can be found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// The cost is proportional to the distance: any median is optimal.
+    Linear,
+    /// The cost is convex and non-decreasing in the distance: the total is convex in the position.
+    Convex,
+    /// Nothing is known about the cost: all positions must be checked.
+    Arbitrary,
+}
+
+/// Fuel spent by a crab to move a given distance.
+pub trait CostModel {
+    fn cost(&self, distance: u32) -> u64;
+
+    fn shape(&self) -> Shape {
+        Shape::Arbitrary
+    }
+}
+
+/// Each step costs 1 fuel.
+pub struct Linear;
+
+impl CostModel for Linear {
+    fn cost(&self, distance: u32) -> u64 {
+        distance as u64
+    }
+
+    fn shape(&self) -> Shape { Shape::Linear }
+}
+
+/// Each step costs 1 more fuel than the previous one.
+pub struct Triangular;
+
+impl CostModel for Triangular {
+    fn cost(&self, distance: u32) -> u64 {
+        let n = distance as u64;
+        n * (n+1) / 2
+    }
+
+    fn shape(&self) -> Shape { Shape::Convex }
+}
+
+/// The cost is the square of the distance.
+pub struct Quadratic;
+
+impl CostModel for Quadratic {
+    fn cost(&self, distance: u32) -> u64 {
+        let n = distance as u64;
+        n * n
+    }
+
+    fn shape(&self) -> Shape { Shape::Convex }
+}
+
+/// Any closure can be used as a cost model, though its optimum has to be searched exhaustively.
+impl<F: Fn(u32) -> u64> CostModel for F {
+    fn cost(&self, distance: u32) -> u64 {
+        self(distance)
+    }
+}
+
+/// A closure that is known to be convex and non-decreasing.
+pub struct Convex<F: Fn(u32) -> u64>(pub F);
+
+impl<F: Fn(u32) -> u64> CostModel for Convex<F> {
+    fn cost(&self, distance: u32) -> u64 {
+        (self.0)(distance)
+    }
+
+    fn shape(&self) -> Shape { Shape::Convex }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: u32,
+    pub cost: u64,
+}
+
+pub fn align_cost<M: CostModel + ?Sized>(crabs: &CrabList, model: &M, position: u32) -> u64 {
+    crabs.iter().map(|&c| model.cost(c.abs_diff(position))).sum()
+}
+
+/// Find the cheapest position to align all crabs on. Ties are broken towards the lowest position.
+pub fn optimize<M: CostModel + ?Sized>(crabs: &CrabList, model: &M) -> Option<Alignment> {
+    let min = *crabs.iter().min()?;
+    let max = *crabs.iter().max()?;
+    let alignment = |position| Alignment { position, cost: align_cost(crabs, model, position) };
+
+    let (lo, hi) = match model.shape() {
+        Shape::Linear => {
+            // Any position between the two middle crabs is a median.
+            let mut sorted = crabs.clone();
+            sorted.sort_unstable();
+            return Some(alignment(sorted[(sorted.len()-1)/2]));
+        },
+        Shape::Convex => {
+            // Integer ternary search, narrowing down to a handful of candidates. On a plateau
+            // both probes are kept, so the optimum can't be dropped, but equally cheap positions
+            // left of it can: walk back over them.
+            let (mut lo, mut hi) = (min, max);
+            while hi - lo > 2 {
+                let m1 = lo + (hi - lo) / 3;
+                let m2 = hi - (hi - lo) / 3;
+                let (c1, c2) = (align_cost(crabs, model, m1), align_cost(crabs, model, m2));
+                if c1 < c2 {
+                    hi = m2;
+                } else if c1 > c2 {
+                    lo = m1;
+                } else {
+                    lo = m1;
+                    hi = m2;
+                }
+            }
+            let mut best = (lo..=hi).map(alignment).min_by_key(|a| (a.cost, a.position))?;
+            while best.position > min && align_cost(crabs, model, best.position - 1) == best.cost {
+                best.position -= 1;
+            }
+            return Some(best);
+        },
+        Shape::Arbitrary => (min, max),
+    };
+
+    (lo..=hi).map(alignment).min_by_key(|a| (a.cost, a.position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRABS: [u32; 10] = [16,1,2,0,4,2,7,1,2,14];
+
+    #[test]
+    fn linear() {
+        let crabs = CRABS.to_vec();
+        assert_eq!(optimize(&crabs, &Linear), Some(Alignment { position: 2, cost: 37 }));
+    }
+
+    #[test]
+    fn triangular() {
+        let crabs = CRABS.to_vec();
+        assert_eq!(optimize(&crabs, &Triangular), Some(Alignment { position: 5, cost: 168 }));
+    }
+
+    #[test]
+    fn closures() {
+        let crabs = CRABS.to_vec();
+        let cube = |n: u32| (n as u64).pow(3);
+        let exhaustive = optimize(&crabs, &cube);
+        assert_eq!(exhaustive, optimize(&crabs, &Convex(cube)));
+        assert_eq!(optimize(&crabs, &|n: u32| n as u64), optimize(&crabs, &Linear));
+
+        // A flat optimum from 2 to 8: the lowest position wins either way.
+        let plateau = |n: u32| n.saturating_sub(8) as u64;
+        let exhaustive = optimize(&vec![0, 10], &plateau);
+        assert_eq!(exhaustive, Some(Alignment { position: 2, cost: 0 }));
+        assert_eq!(optimize(&vec![0, 10], &Convex(plateau)), exhaustive);
+    }
+}
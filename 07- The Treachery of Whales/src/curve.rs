@@ -0,0 +1,105 @@
+use std::io::{self, Write};
+
+use crate::CrabList;
+
+/// Total alignment cost at every position between the leftmost and rightmost crab, for each of
+/// the polynomial cost models.
+#[derive(Clone, Debug)]
+pub struct CostCurve {
+    start: u32,
+    linear: Vec<u128>,
+    triangular: Vec<u128>,
+    quadratic: Vec<u128>,
+}
+
+impl CostCurve {
+    /// Compute the curve in O(n + range).
+    // For a position p, the costs only depend on the number of crabs on each side of p, the sum of
+    // their positions and the sum of their squared positions: e.g. for the crabs to the left,
+    //   Σ(p-c) = p·N - Σc  and  Σ(p-c)² = p²·N - 2p·Σc + Σc²
+    // Sweeping p from left to right, these moments are updated incrementally from the crab
+    // counts at each position.
+    pub fn compute(crabs: &CrabList) -> Self {
+        let range = crabs.iter().fold(None, |range, &c| match range {
+            None => Some((c, c)),
+            Some((lo, hi)) => Some((c.min(lo), c.max(hi))),
+        });
+        let (start, end) = match range {
+            Some(range) => range,
+            None => return CostCurve { start: 0, linear: vec![], triangular: vec![], quadratic: vec![] },
+        };
+
+        let mut counts = vec![0u64; (end - start) as usize + 1];
+        for c in crabs {
+            counts[(c - start) as usize] += 1;
+        }
+
+        // (count, Σc, Σc²) of the crabs to the left of (or at) p, and strictly to the right of p.
+        let moments = |c: u32, n: u64| {
+            let (c, n) = (c as i128, n as i128);
+            (n, n * c, n * c * c)
+        };
+        let mut left = (0i128, 0i128, 0i128);
+        let mut right = crabs.iter().fold((0i128, 0i128, 0i128), |acc, &c| {
+            let m = moments(c, 1);
+            (acc.0 + m.0, acc.1 + m.1, acc.2 + m.2)
+        });
+
+        let mut curve = CostCurve {
+            start,
+            linear: Vec::with_capacity(counts.len()),
+            triangular: Vec::with_capacity(counts.len()),
+            quadratic: Vec::with_capacity(counts.len()),
+        };
+        for (i, &n) in counts.iter().enumerate() {
+            let p = start + i as u32;
+            let m = moments(p, n);
+            left = (left.0 + m.0, left.1 + m.1, left.2 + m.2);
+            right = (right.0 - m.0, right.1 - m.1, right.2 - m.2);
+
+            let p = p as i128;
+            let linear = (p * left.0 - left.1) + (right.1 - p * right.0);
+            let quadratic = (p * p * left.0 - 2 * p * left.1 + left.2)
+                + (right.2 - 2 * p * right.1 + p * p * right.0);
+            curve.linear.push(linear as u128);
+            curve.quadratic.push(quadratic as u128);
+            curve.triangular.push(((quadratic + linear) / 2) as u128);
+        }
+
+        curve
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.linear.len()).map(move |i| self.start + i as u32)
+    }
+
+    pub fn linear(&self) -> &[u128] { &self.linear }
+    pub fn triangular(&self) -> &[u128] { &self.triangular }
+    pub fn quadratic(&self) -> &[u128] { &self.quadratic }
+
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "position,linear,triangular,quadratic")?;
+        for (i, p) in self.positions().enumerate() {
+            writeln!(out, "{},{},{},{}", p, self.linear[i], self.triangular[i], self.quadratic[i])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::{align_cost, Linear, Quadratic, Triangular};
+
+    #[test]
+    fn matches_align_cost() {
+        let crabs = vec![16,1,2,0,4,2,7,1,2,14];
+        let curve = CostCurve::compute(&crabs);
+        for (i, p) in curve.positions().enumerate() {
+            assert_eq!(curve.linear()[i], align_cost(&crabs, &Linear, p) as u128);
+            assert_eq!(curve.triangular()[i], align_cost(&crabs, &Triangular, p) as u128);
+            assert_eq!(curve.quadratic()[i], align_cost(&crabs, &Quadratic, p) as u128);
+        }
+        assert_eq!(curve.positions().count(), 17);
+    }
+}
@@ -1,5 +1,9 @@
 use std::io::{self, BufRead};
 
+pub mod cost;
+pub mod curve;
+pub mod plane;
+
 pub type CrabList = Vec<u32>;
 
 pub fn parse_stdin() -> CrabList {
@@ -13,12 +17,15 @@ pub fn parse_stdin() -> CrabList {
     crabs
 }
 
-pub fn sign(a: u32, b: u32) -> &'static str {
-    if a < b {
-        "<"
-    } else if a == b {
-        "="
-    } else {
-        ">"
-    }
+/// Print some statistics about the crabs.
+pub fn print_stats(crabs: &CrabList) {
+    let mut sorted = crabs.clone();
+    sorted.sort_unstable();
+
+    println!("crabs: {}", sorted.len());
+    println!("median: {}", sorted[sorted.len()/2]);
+    println!("mean: {}", sorted.iter().map(|&c| c as u64).sum::<u64>() / (sorted.len() as u64));
+    println!("min: {}", sorted[0]);
+    println!("max: {}", sorted[sorted.len()-1]);
+    println!();
 }
@@ -0,0 +1,139 @@
+use std::io::{self, BufRead};
+
+pub type Point = (i32, i32);
+pub type CrabList2D = Vec<Point>;
+
+/// Parse whitespace-separated `x,y` positions.
+pub fn parse_stdin_2d() -> CrabList2D {
+    let mut crabs: CrabList2D = Vec::new();
+
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        for pos in line.unwrap().split_whitespace() {
+            let (x, y) = pos.split_once(',').unwrap();
+            crabs.push((x.parse().unwrap(), y.parse().unwrap()));
+        }
+    }
+
+    crabs
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Alignment2D<C> {
+    pub position: Point,
+    pub cost: C,
+}
+
+pub fn manhattan_cost(crabs: &CrabList2D, position: Point) -> u64 {
+    crabs.iter().map(|&(x, y)| x.abs_diff(position.0) as u64 + y.abs_diff(position.1) as u64).sum()
+}
+
+pub fn euclidean_cost(crabs: &CrabList2D, position: Point) -> f64 {
+    let position = (position.0 as f64, position.1 as f64);
+    crabs.iter().map(|&(x, y)| distance((x as f64, y as f64), position)).sum()
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Find the lattice point minimizing the total Manhattan distance. The cost is separable, so the
+/// medians of each coordinate are optimal.
+pub fn optimize_manhattan(crabs: &CrabList2D) -> Option<Alignment2D<u64>> {
+    if crabs.is_empty() {
+        return None;
+    }
+    let median = |mut coords: Vec<i32>| {
+        coords.sort_unstable();
+        coords[(coords.len()-1)/2]
+    };
+    let position = (median(crabs.iter().map(|c| c.0).collect()), median(crabs.iter().map(|c| c.1).collect()));
+
+    Some(Alignment2D { position, cost: manhattan_cost(crabs, position) })
+}
+
+const WEISZFELD_ITERATIONS: usize = 1000;
+const WEISZFELD_EPSILON: f64 = 1e-9;
+
+/// Find the geometric median of the crabs with Weiszfeld's algorithm.
+pub fn geometric_median(crabs: &CrabList2D) -> Option<(f64, f64)> {
+    if crabs.is_empty() {
+        return None;
+    }
+    let points: Vec<(f64, f64)> = crabs.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+
+    // Start from the centroid.
+    let n = points.len() as f64;
+    let mut estimate = points.iter().fold((0.0, 0.0), |acc, p| (acc.0 + p.0 / n, acc.1 + p.1 / n));
+    for _ in 0..WEISZFELD_ITERATIONS {
+        let (mut num, mut den) = ((0.0, 0.0), 0.0);
+        for &p in &points {
+            let d = distance(p, estimate);
+            // Points right on the estimate would divide by zero, skip them.
+            if d < WEISZFELD_EPSILON {
+                continue;
+            }
+            num = (num.0 + p.0 / d, num.1 + p.1 / d);
+            den += 1.0 / d;
+        }
+        if den == 0.0 {
+            break;
+        }
+        let next = (num.0 / den, num.1 / den);
+        let delta = distance(next, estimate);
+        estimate = next;
+        if delta < WEISZFELD_EPSILON {
+            break;
+        }
+    }
+
+    Some(estimate)
+}
+
+/// Find the lattice point minimizing the total Euclidean distance: the geometric median, rounded
+/// to the nearest lattice point and then improved by local descent.
+pub fn optimize_euclidean(crabs: &CrabList2D) -> Option<Alignment2D<f64>> {
+    let (x, y) = geometric_median(crabs)?;
+    let mut best = Alignment2D { position: (x.round() as i32, y.round() as i32), cost: 0.0 };
+    best.cost = euclidean_cost(crabs, best.position);
+
+    loop {
+        let (x, y) = best.position;
+        let neighbor = (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+            .map(|position| Alignment2D { position, cost: euclidean_cost(crabs, position) })
+            .min_by(|a, b| a.cost.total_cmp(&b.cost))
+            .unwrap();
+        if neighbor.cost < best.cost {
+            best = neighbor;
+        } else {
+            break;
+        }
+    }
+
+    Some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manhattan() {
+        let crabs = vec![(0,0), (1,5), (2,1), (10,2)];
+        let optimum = optimize_manhattan(&crabs).unwrap();
+        assert_eq!(optimum, Alignment2D { position: (1,1), cost: 1+1 + 4 + 1 + 9+1 });
+    }
+
+    #[test]
+    fn euclidean() {
+        // The Fermat point of this triangle is close to (5,3).
+        let crabs = vec![(0,0), (10,0), (5,9)];
+        let optimum = optimize_euclidean(&crabs).unwrap();
+        assert_eq!(optimum.position, (5,3));
+        for x in -2..=12 {
+            for y in -2..=11 {
+                assert!(euclidean_cost(&crabs, (x,y)) >= optimum.cost);
+            }
+        }
+    }
+}
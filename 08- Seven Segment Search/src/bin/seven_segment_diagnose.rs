@@ -0,0 +1,39 @@
+use std::error::Error;
+
+use seven_segment_search::diagnosis::diagnose;
+use seven_segment_search::font::Font;
+use seven_segment_search::parse_stdin;
+
+const MAX_FAULTS: usize = 2;
+const MAX_EXPLANATIONS: usize = 1000;
+
+fn main() -> Result<(),Box<dyn Error>> {
+    let displays = parse_stdin();
+    let font = Font::digits();
+
+    for (i, d) in displays.iter().enumerate() {
+        let diagnosis = diagnose(&font, &d.digits, MAX_FAULTS, MAX_EXPLANATIONS)?;
+        println!("display #{}: {} fault(s), {} explanation(s)", i, diagnosis.faults(), diagnosis.explanations.len());
+        for e in &diagnosis.explanations {
+            let faults: Vec<String> = e.faults.iter().map(|f| f.to_string()).collect();
+            let wiring: String = ('a'..='g').map(|w| e.mapping.segment(w).unwrap()).collect();
+            println!("  wiring abcdefg -> {} [{}]", wiring, faults.join(", "));
+        }
+        if diagnosis.truncated {
+            println!("  (more explanations left out)");
+        }
+
+        let mut output = Vec::new();
+        for o in &d.output {
+            let readings = diagnosis.readings(&font, o)?;
+            output.push(match readings.len() {
+                0 => "?".to_owned(),
+                1 => readings[0].to_string(),
+                _ => format!("{{{}}}", readings.iter().collect::<String>()),
+            });
+        }
+        println!("  output: {}", output.join(" "));
+    }
+
+    Ok(())
+}
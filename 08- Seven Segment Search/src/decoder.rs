@@ -0,0 +1,205 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::font::{segments, Font, Segments};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A pattern uses a wire that doesn't exist on the display.
+    InvalidPattern(String),
+    /// No wire mapping can explain all the patterns.
+    NoMapping,
+    /// Several wire mappings can explain all the patterns.
+    Ambiguous,
+    /// A pattern doesn't show any glyph of the font.
+    UnknownGlyph(String),
+    /// A font has more segments than fit in [`Segments`].
+    TooManySegments(usize),
+    /// A glyph of a font uses a segment that doesn't exist.
+    InvalidGlyph(char, String),
+    /// A glyph of a font lights the same segments as another one.
+    DuplicateGlyph(char),
+    /// A diagnosis was asked for no explanations at all.
+    ZeroLimit,
+    /// A diagnosis has too many faulty segments to enumerate their kinds.
+    TooManyFaults(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPattern(p) => write!(f, "invalid pattern: {}", p),
+            Self::NoMapping => write!(f, "no consistent wire mapping"),
+            Self::Ambiguous => write!(f, "ambiguous wire mapping"),
+            Self::UnknownGlyph(p) => write!(f, "unknown glyph: {}", p),
+            Self::TooManySegments(n) => write!(f, "too many segments: {}", n),
+            Self::InvalidGlyph(g, s) => write!(f, "invalid segments for glyph {}: {}", g, s),
+            Self::DuplicateGlyph(g) => write!(f, "glyph {} is not unique", g),
+            Self::ZeroLimit => write!(f, "the limit of explanations must be positive"),
+            Self::TooManyFaults(k) => write!(f, "too many faulty segments: {}", k),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Which segment each wire is connected to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Mapping {
+    /// Segment index, for each wire index.
+    wires: Vec<usize>,
+}
+
+impl Mapping {
+    /// The segment connected to a wire.
+    pub fn segment(&self, wire: char) -> Option<char> {
+        let i = (wire as u32).checked_sub('a' as u32)? as usize;
+        self.wires.get(i).map(|&s| (b'a' + s as u8) as char)
+    }
+
+    /// The segments lit by a pattern of wires.
+    pub fn translate(&self, pattern: &str) -> Result<Segments, DecodeError> {
+        let wires = segments(pattern, self.wires.len()).ok_or_else(|| DecodeError::InvalidPattern(pattern.to_owned()))?;
+        Ok(self.translate_wires(wires))
+    }
+
+    fn translate_wires(&self, wires: Segments) -> Segments {
+        self.wires.iter().enumerate().filter(|(w, _)| wires & 1 << w != 0).fold(0, |segs, (_, s)| segs | 1 << s)
+    }
+
+    /// The glyph shown by a pattern of wires.
+    pub fn decode(&self, font: &Font, pattern: &str) -> Result<char, DecodeError> {
+        font.glyph(self.translate(pattern)?).ok_or_else(|| DecodeError::UnknownGlyph(pattern.to_owned()))
+    }
+}
+
+/// Parse patterns of wires, dropping duplicates.
+pub(crate) fn parse_patterns<S: AsRef<str>>(font: &Font, patterns: &[S]) -> Result<Vec<Segments>, DecodeError> {
+    let mut wires: Vec<Segments> = Vec::with_capacity(patterns.len());
+    for p in patterns {
+        let p = p.as_ref();
+        let w = segments(p, font.segments()).ok_or_else(|| DecodeError::InvalidPattern(p.to_owned()))?;
+        if !wires.contains(&w) {
+            wires.push(w);
+        }
+    }
+    Ok(wires)
+}
+
+/// Backtracking search over wire mappings. Each pattern keeps the list of glyphs it can still
+/// show given the wires assigned so far; a branch is cut as soon as some pattern has none left.
+pub(crate) struct Search {
+    pub segments: usize,
+    pub glyphs: Vec<Segments>,
+    pub patterns: Vec<Segments>,
+    pub limit: usize,
+    pub found: Vec<Mapping>,
+}
+
+impl Search {
+    pub fn run(&mut self) {
+        let candidates: Vec<Vec<Segments>> = self.patterns.iter().map(|p|
+            self.glyphs.iter().copied().filter(|s| s.count_ones() == p.count_ones()).collect()
+        ).collect();
+        let mut wires = Vec::with_capacity(self.segments);
+        self.assign(&mut wires, 0, &candidates);
+    }
+
+    fn assign(&mut self, wires: &mut Vec<usize>, used: Segments, candidates: &[Vec<Segments>]) {
+        if self.found.len() >= self.limit {
+            return;
+        }
+        let w = wires.len();
+        if w == self.segments {
+            self.found.push(Mapping { wires: wires.clone() });
+            return;
+        }
+
+        for s in 0..self.segments {
+            if used & 1 << s != 0 {
+                continue;
+            }
+            // Keep the glyphs that agree with the pattern on wire w being connected to segment s.
+            let filtered: Option<Vec<Vec<Segments>>> = self.patterns.iter().zip(candidates).map(|(p, c)| {
+                let lit = p & 1 << w != 0;
+                let c: Vec<_> = c.iter().copied().filter(|g| (g & 1 << s != 0) == lit).collect();
+                if c.is_empty() { None } else { Some(c) }
+            }).collect();
+
+            if let Some(filtered) = filtered {
+                wires.push(s);
+                self.assign(wires, used | 1 << s, &filtered);
+                wires.pop();
+            }
+        }
+    }
+}
+
+/// Find up to `limit` wire mappings under which every pattern shows a glyph of the font.
+pub fn mappings<S: AsRef<str>>(font: &Font, patterns: &[S], limit: usize) -> Result<Vec<Mapping>, DecodeError> {
+    let patterns = parse_patterns(font, patterns)?;
+    let glyphs = font.glyphs().iter().map(|(_, s)| *s).collect();
+    let mut search = Search { segments: font.segments(), glyphs, patterns, limit, found: Vec::new() };
+    search.run();
+    Ok(search.found)
+}
+
+/// Find the only wire mapping under which every pattern shows a glyph of the font.
+pub fn decode<S: AsRef<str>>(font: &Font, patterns: &[S]) -> Result<Mapping, DecodeError> {
+    let mut found = mappings(font, patterns, 2)?;
+    match found.len() {
+        0 => Err(DecodeError::NoMapping),
+        1 => Ok(found.pop().unwrap()),
+        _ => Err(DecodeError::Ambiguous),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scramble the wires of every glyph of the font.
+    fn scramble(font: &Font, permutation: &[usize]) -> Vec<String> {
+        font.glyphs().iter().map(|(_, s)|
+            (0..font.segments()).filter(|i| s & 1 << i != 0).map(|i| (b'a' + permutation[i] as u8) as char).collect()
+        ).collect()
+    }
+
+    fn check_font(font: Font) {
+        let permutation: Vec<usize> = (0..font.segments()).rev().collect();
+        let patterns = scramble(&font, &permutation);
+        let mapping = decode(&font, &patterns).unwrap();
+        for ((g, _), p) in font.glyphs().iter().zip(&patterns) {
+            assert_eq!(mapping.decode(&font, p), Ok(*g));
+        }
+    }
+
+    #[test]
+    fn puzzle() {
+        let patterns: Vec<_> = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab".split_whitespace().collect();
+        let font = Font::digits();
+        let mapping = decode(&font, &patterns).unwrap();
+        let output: String = ["cdfeb", "fcadb", "cdfeb", "cdbaf"].iter().map(|p| mapping.decode(&font, p).unwrap()).collect();
+        assert_eq!(output, "5353");
+        assert_eq!(mapping.segment('d'), Some('a'));
+    }
+
+    #[test]
+    fn fonts() {
+        check_font(Font::digits());
+        check_font(Font::hex());
+        check_font(Font::alphanumeric14());
+    }
+
+    #[test]
+    fn errors() {
+        let font = Font::digits();
+        assert_eq!(decode(&font, &["ab", "abc", "xyz"]), Err(DecodeError::InvalidPattern("xyz".to_owned())));
+        assert_eq!(decode(&font, &["ab", "abd"]), Err(DecodeError::Ambiguous));
+        assert_eq!(decode(&font, &["a"]), Err(DecodeError::NoMapping));
+
+        assert_eq!(Font::new(33, &[]).err(), Some(DecodeError::TooManySegments(33)));
+        assert_eq!(Font::new(3, &[('1', "ab"), ('2', "ad")]).err(), Some(DecodeError::InvalidGlyph('2', "ad".to_owned())));
+        assert_eq!(Font::new(3, &[('1', "ab"), ('2', "ba")]).err(), Some(DecodeError::DuplicateGlyph('2')));
+    }
+}
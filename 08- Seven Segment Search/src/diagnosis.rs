@@ -0,0 +1,164 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::decoder::{parse_patterns, DecodeError, Mapping, Search};
+use crate::font::{Font, Segments};
+
+/// A broken segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// The segment never lights up.
+    Dead(char),
+    /// The segment is always lit.
+    Shorted(char),
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dead(s) => write!(f, "{} dead", s),
+            Self::Shorted(s) => write!(f, "{} shorted", s),
+        }
+    }
+}
+
+fn index(segment: char) -> usize {
+    (segment as u8 - b'a') as usize
+}
+
+/// The segments actually lit when showing a glyph on a faulty display.
+fn apply(faults: &[Fault], glyph: Segments) -> Segments {
+    faults.iter().fold(glyph, |segs, fault| match *fault {
+        Fault::Dead(s) => segs & !(1 << index(s)),
+        Fault::Shorted(s) => segs | 1 << index(s),
+    })
+}
+
+/// A set of faults and a wire mapping that together explain the observed patterns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub faults: Vec<Fault>,
+    pub mapping: Mapping,
+}
+
+impl Explanation {
+    /// The glyphs that could have shown a pattern under this explanation.
+    pub fn readings(&self, font: &Font, pattern: &str) -> Result<Vec<char>, DecodeError> {
+        let segs = self.mapping.translate(pattern)?;
+        Ok(font.glyphs().iter().filter(|(_, g)| apply(&self.faults, *g) == segs).map(|(c, _)| *c).collect())
+    }
+}
+
+/// All the explanations of the observed patterns with the fewest faults. There is at least one.
+#[derive(Clone, Debug)]
+pub struct Diagnosis {
+    pub explanations: Vec<Explanation>,
+    /// Whether there were more explanations than the limit, and some were left out.
+    pub truncated: bool,
+}
+
+impl Diagnosis {
+    /// Number of faulty segments assumed by the explanations.
+    pub fn faults(&self) -> usize {
+        self.explanations[0].faults.len()
+    }
+
+    /// The glyphs that could have shown a pattern, under any of the explanations. More than one
+    /// means that the pattern is ambiguous.
+    pub fn readings(&self, font: &Font, pattern: &str) -> Result<Vec<char>, DecodeError> {
+        let mut readings = Vec::new();
+        for e in &self.explanations {
+            for g in e.readings(font, pattern)? {
+                if !readings.contains(&g) {
+                    readings.push(g);
+                }
+            }
+        }
+        readings.sort_unstable();
+        Ok(readings)
+    }
+}
+
+/// Explain the observed patterns with as few faulty segments as possible, up to `max_faults`.
+/// At most `limit` explanations are returned, which must be at least 1.
+pub fn diagnose<S: AsRef<str>>(font: &Font, patterns: &[S], max_faults: usize, limit: usize) -> Result<Diagnosis, DecodeError> {
+    if limit == 0 {
+        return Err(DecodeError::ZeroLimit);
+    }
+    let patterns = parse_patterns(font, patterns)?;
+    let segments: Vec<char> = (0..font.segments()).map(|i| (b'a' + i as u8) as char).collect();
+
+    for k in 0..=max_faults.min(font.segments()) {
+        // Each faulty segment can be either dead or shorted.
+        let kinds_count = 1u32.checked_shl(k as u32).ok_or(DecodeError::TooManyFaults(k))?;
+        let mut explanations = Vec::new();
+        // Look for one explanation past the limit, to tell whether the list is complete.
+        'search: for faulty in segments.iter().copied().combinations(k) {
+            for kinds in 0..kinds_count {
+                if explanations.len() > limit {
+                    break 'search;
+                }
+                let faults: Vec<Fault> = faulty.iter().enumerate().map(|(i, &s)|
+                    if kinds & 1 << i == 0 { Fault::Dead(s) } else { Fault::Shorted(s) }
+                ).collect();
+                let glyphs = font.glyphs().iter().map(|(_, g)| apply(&faults, *g)).collect();
+                let mut search = Search {
+                    segments: font.segments(),
+                    glyphs,
+                    patterns: patterns.clone(),
+                    limit: limit + 1 - explanations.len(),
+                    found: Vec::new(),
+                };
+                search.run();
+                explanations.extend(search.found.into_iter().map(|mapping| Explanation { faults: faults.clone(), mapping }));
+            }
+        }
+        if !explanations.is_empty() {
+            let truncated = explanations.len() > limit;
+            explanations.truncate(limit);
+            return Ok(Diagnosis { explanations, truncated });
+        }
+    }
+
+    Err(DecodeError::NoMapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: &str = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+
+    #[test]
+    fn healthy() {
+        let patterns: Vec<_> = PATTERNS.split_whitespace().collect();
+        let diagnosis = diagnose(&Font::digits(), &patterns, 2, 10).unwrap();
+        assert_eq!(diagnosis.faults(), 0);
+        assert_eq!(diagnosis.explanations.len(), 1);
+        assert!(!diagnosis.truncated);
+    }
+
+    #[test]
+    fn dead_segment() {
+        // Wire d is connected to segment a: kill it.
+        let patterns: Vec<String> = PATTERNS.split_whitespace().map(|p| p.replace('d', "")).collect();
+        let font = Font::digits();
+        assert_eq!(crate::decoder::decode(&font, &patterns), Err(DecodeError::NoMapping));
+
+        let diagnosis = diagnose(&font, &patterns, 2, 10).unwrap();
+        assert_eq!(diagnosis.faults(), 1);
+        // Segments a and g are lit by the same digits, except for 7: either can be dead.
+        let faults: Vec<_> = diagnosis.explanations.iter().map(|e| e.faults[0]).collect();
+        assert_eq!(faults, vec![Fault::Dead('a'), Fault::Dead('g')]);
+        // 1 and 7 now look the same.
+        assert_eq!(diagnosis.readings(&font, "ab").unwrap(), vec!['1', '7']);
+        assert_eq!(diagnosis.readings(&font, "cfbe").unwrap(), vec!['5']);
+        assert!(!diagnosis.truncated);
+
+        let diagnosis = diagnose(&font, &patterns, 2, 1).unwrap();
+        assert_eq!(diagnosis.explanations.len(), 1);
+        assert!(diagnosis.truncated);
+        assert_eq!(diagnose(&font, &patterns, 2, 0).err(), Some(DecodeError::ZeroLimit));
+    }
+}
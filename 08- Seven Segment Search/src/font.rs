@@ -0,0 +1,112 @@
+use crate::decoder::DecodeError;
+
+/// A set of segments, one bit per segment.
+pub type Segments = u32;
+
+/// The glyphs that can be shown on a display, and which segments they light up. Segments are
+/// named with consecutive letters starting from `a`.
+#[derive(Clone, Debug)]
+pub struct Font {
+    segments: usize,
+    glyphs: Vec<(char, Segments)>,
+}
+
+/// Parse a string of segment letters into a set of segments.
+pub fn segments(pattern: &str, count: usize) -> Option<Segments> {
+    pattern.chars().try_fold(0, |segs, c| {
+        let i = (c as u32).checked_sub('a' as u32)?;
+        if (i as usize) < count {
+            Some(segs | 1 << i)
+        } else {
+            None
+        }
+    })
+}
+
+impl Font {
+    /// A font from the segments lit by each glyph. Every glyph must light a distinct set of
+    /// segments.
+    pub fn new(segments: usize, glyphs: &[(char, &str)]) -> Result<Self, DecodeError> {
+        if segments > Segments::BITS as usize {
+            return Err(DecodeError::TooManySegments(segments));
+        }
+        let mut parsed: Vec<(char, Segments)> = Vec::with_capacity(glyphs.len());
+        for &(g, s) in glyphs {
+            let segs = self::segments(s, segments).ok_or_else(|| DecodeError::InvalidGlyph(g, s.to_owned()))?;
+            if parsed.iter().any(|&(_, t)| t == segs) {
+                return Err(DecodeError::DuplicateGlyph(g));
+            }
+            parsed.push((g, segs));
+        }
+        Ok(Font { segments, glyphs: parsed })
+    }
+
+    /// The standard digits, as in the puzzle:
+    ///  aaaa
+    /// b    c
+    /// b    c
+    ///  dddd
+    /// e    f
+    /// e    f
+    ///  gggg
+    pub fn digits() -> Self {
+        Font::new(7, &DIGITS).expect("invalid built-in font")
+    }
+
+    /// The standard digits, plus the hexadecimal digits A b C d E F.
+    pub fn hex() -> Self {
+        let mut glyphs = DIGITS.to_vec();
+        glyphs.extend_from_slice(&[
+            ('A', "abcdef"),
+            ('b', "bdefg"),
+            ('C', "abeg"),
+            ('d', "cdefg"),
+            ('E', "abdeg"),
+            ('F', "abde"),
+        ]);
+        Font::new(7, &glyphs).expect("invalid built-in font")
+    }
+
+    /// Capital letters and digits on a 14-segment display:
+    ///  aaaaa
+    /// fi j kb
+    /// f ijk b
+    ///  gg hh
+    /// e lmn c
+    /// el m nc
+    ///  ddddd
+    pub fn alphanumeric14() -> Self {
+        Font::new(14, &[
+            ('A', "abcefgh"), ('B', "abcdhjm"), ('C', "adef"), ('D', "abcdjm"),
+            ('E', "adefg"), ('F', "aefg"), ('G', "acdefh"), ('H', "bcefgh"),
+            ('I', "adjm"), ('J', "bcde"), ('K', "efgkn"), ('L', "def"),
+            ('M', "bcefik"), ('N', "bcefin"), ('O', "abcdef"), ('P', "abefgh"),
+            ('Q', "abcdefn"), ('R', "abefghn"), ('S', "acdhi"), ('T', "ajm"),
+            ('U', "bcdef"), ('V', "efkl"), ('W', "bcefln"), ('X', "ikln"),
+            ('Y', "ikm"), ('Z', "adkl"),
+            ('0', "abcdefkl"), ('1', "bck"), ('2', "abdegh"), ('3', "abcdh"),
+            ('4', "bcfgh"), ('5', "acdfgh"), ('6', "acdefgh"), ('7', "abc"),
+            ('8', "abcdefgh"), ('9', "abcdfgh"),
+        ]).expect("invalid built-in font")
+    }
+
+    pub fn segments(&self) -> usize { self.segments }
+    pub fn glyphs(&self) -> &[(char, Segments)] { &self.glyphs }
+
+    pub fn glyph(&self, segments: Segments) -> Option<char> {
+        self.glyphs.iter().find(|(_, s)| *s == segments).map(|(g, _)| *g)
+    }
+}
+
+const DIGITS: [(char, &str); 10] = [
+    ('0', "abcefg"),
+    ('1', "cf"),
+    ('2', "acdeg"),
+    ('3', "acdfg"),
+    ('4', "bcdf"),
+    ('5', "abdfg"),
+    ('6', "abdefg"),
+    ('7', "acf"),
+    ('8', "abcdefg"),
+    ('9', "abcdfg"),
+];
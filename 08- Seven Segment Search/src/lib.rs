@@ -1,5 +1,9 @@
 use std::io::{self, BufRead};
 
+pub mod decoder;
+pub mod diagnosis;
+pub mod font;
+
 pub struct Display {
     pub digits: Vec<String>,
     pub output: Vec<String>,
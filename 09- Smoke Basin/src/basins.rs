@@ -0,0 +1,108 @@
+use std::collections::VecDeque;
+
+use crate::{neighbors, Height, Map, Neighborhood};
+
+pub type Point = (usize, usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Basin {
+    pub size: usize,
+    /// The lowest cell of the basin (the first one in row-major order, if there are several).
+    pub lowest: Point,
+    /// Cells of the basin next to a barrier or to the edge of the map.
+    pub boundary: Vec<Point>,
+}
+
+/// The map split into basins.
+pub struct Basins {
+    /// Index of the basin of each cell, `None` for barriers.
+    pub labels: grid::Grid<Option<usize>>,
+    pub basins: Vec<Basin>,
+}
+
+impl Basins {
+    /// Basins sorted by decreasing size.
+    pub fn largest(&self) -> Vec<&Basin> {
+        let mut basins: Vec<_> = self.basins.iter().collect();
+        basins.sort_by_key(|b| std::cmp::Reverse(b.size));
+        basins
+    }
+}
+
+/// Split the map into basins: connected areas of cells lower than `barrier`.
+pub fn segment(map: &Map, barrier: Height) -> Basins {
+    let (n, m) = map.size();
+    let mut labels: grid::Grid<Option<usize>> = grid::Grid::init(n, m, None);
+    let mut basins = Vec::new();
+
+    for i in 0..n {
+        for j in 0..m {
+            if map[i][j] >= barrier || labels[i][j].is_some() {
+                continue;
+            }
+
+            // Flood fill a new basin.
+            let label = basins.len();
+            let mut basin = Basin { size: 0, lowest: (i, j), boundary: Vec::new() };
+            let mut queue = VecDeque::from([(i, j)]);
+            labels[i][j] = Some(label);
+            while let Some((x, y)) = queue.pop_front() {
+                basin.size += 1;
+                if map[x][y] < map[basin.lowest.0][basin.lowest.1]
+                    || (map[x][y] == map[basin.lowest.0][basin.lowest.1] && (x, y) < basin.lowest) {
+                    basin.lowest = (x, y);
+                }
+
+                let neighbors = neighbors(map, x, y, Neighborhood::Four);
+                if neighbors.len() < 4 || neighbors.iter().any(|&(a, b)| map[a][b] >= barrier) {
+                    basin.boundary.push((x, y));
+                }
+                for (a, b) in neighbors {
+                    if map[a][b] < barrier && labels[a][b].is_none() {
+                        labels[a][b] = Some(label);
+                        queue.push_back((a, b));
+                    }
+                }
+            }
+            basin.boundary.sort_unstable();
+            basins.push(basin);
+        }
+    }
+
+    Basins { labels, basins }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Map {
+        let cells: Vec<Height> = "\
+            2199943210\
+            3987894921\
+            9856789892\
+            8767896789\
+            9899965678".bytes().map(|b| (b - b'0') as Height).collect();
+        Map::from_vec(cells, 10)
+    }
+
+    #[test]
+    fn puzzle() {
+        let basins = segment(&example(), 9);
+        let sizes: Vec<_> = basins.largest().iter().map(|b| b.size).collect();
+        assert_eq!(sizes, vec![14, 9, 9, 3]);
+        assert_eq!(basins.basins[0], Basin { size: 3, lowest: (0, 1), boundary: vec![(0, 0), (0, 1), (1, 0)] });
+        assert_eq!(basins.labels[2][2], basins.labels[3][1]);
+        assert_eq!(basins.labels[2][0], None);
+    }
+
+    #[test]
+    fn plateau() {
+        // A flat basin has a single lowest point, and no cell is left out.
+        let map = Map::from_vec(vec![5,5,9,5, 5,5,9,5], 4);
+        let basins = segment(&map, 9);
+        assert_eq!(basins.basins.len(), 2);
+        assert_eq!(basins.basins[0].lowest, (0, 0));
+        assert_eq!(basins.basins[0].size, 4);
+    }
+}
@@ -1,19 +1,16 @@
-use smoke_basin::{find_lowest_neighbor, parse_stdin};
+use std::error::Error;
 
-fn main() {
-    let map = parse_stdin();
+use smoke_basin::{low_points, parse_stdin, Neighborhood};
 
-    let mut risk: u32 = 0;
+fn main() -> Result<(),Box<dyn Error>> {
+    let map = parse_stdin()?;
 
-    let (n, m) = map.size();
-    for i in 0..n {
-        for j in 0..m {
-            let v = map[i][j];
-            if find_lowest_neighbor(&map, i, j) == None {
-                println!("basin bottom @ ({}, {})", i, j);
-                risk += 1 + v as u32;
-            }
-        }
+    let mut risk: u32 = 0;
+    for (i, j) in low_points(&map, Neighborhood::Four) {
+        println!("basin bottom @ ({}, {})", i, j);
+        risk += 1 + map[i][j] as u32;
     }
     println!("risk level: {}", risk);
+
+    Ok(())
 }
@@ -1,33 +1,25 @@
-use std::collections::HashMap;
+use std::error::Error;
+use std::io;
 
-use smoke_basin::{find_lowest_neighbor, parse_stdin};
+use smoke_basin::basins::segment;
+use smoke_basin::render::{render, write_ansi};
+use smoke_basin::{low_points, parse_stdin, Neighborhood};
 
-fn main() {
-    let map = parse_stdin();
+fn main() -> Result<(),Box<dyn Error>> {
+    let map = parse_stdin()?;
 
-    let mut basins: HashMap<(usize, usize), u32> = HashMap::new();
+    let basins = segment(&map, 9);
+    let image = render(&map, &basins, &low_points(&map, Neighborhood::Four));
+    write_ansi(&map, &image, &mut io::stderr().lock())?;
 
-    let (n, m) = map.size();
-    for i in 0..n {
-        for j in 0..m {
-            eprint!("({},{}):{}", i, j, map[i][j]);
-            let mut bottom = if map[i][j] != 9 {
-                Some((i, j))
-            } else { None };
-            while bottom != None {
-                let (x, y) = bottom.unwrap();
-                bottom = find_lowest_neighbor(&map, x, y);
-                match bottom {
-                    None => { basins.insert((x, y), basins.get(&(x, y)).unwrap_or(&0) + 1); },
-                    Some((a,b)) => { eprint!(" -> ({},{}):{}", a, b, map[a][b]); },
-                }
-            }
-            eprintln!("");
-        }
-    }
-    let mut basins: Vec<((usize, usize), u32)> = basins.into_iter().collect();
-    basins.sort_unstable_by_key(|(_,s)| *s);
-    for ((i,j), size) in basins.iter() {
-        println!("basin @ ({}, {}): size {}", i, j, size);
+    let largest = basins.largest();
+    for basin in &largest {
+        let (i, j) = basin.lowest;
+        println!("basin @ ({}, {}): size {}", i, j, basin.size);
     }
+
+    let product: usize = largest.iter().take(3).map(|b| b.size).product();
+    println!("3 largest basins: {}", product);
+
+    Ok(())
 }
@@ -0,0 +1,16 @@
+use std::error::Error;
+use std::io;
+
+use smoke_basin::basins::segment;
+use smoke_basin::render::{render, write_ppm};
+use smoke_basin::{low_points, parse_stdin, Neighborhood};
+
+fn main() -> Result<(),Box<dyn Error>> {
+    let map = parse_stdin()?;
+
+    let basins = segment(&map, 9);
+    let image = render(&map, &basins, &low_points(&map, Neighborhood::Four));
+    write_ppm(&image, &mut io::stdout().lock())?;
+
+    Ok(())
+}
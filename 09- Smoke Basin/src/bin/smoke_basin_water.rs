@@ -0,0 +1,22 @@
+use std::error::Error;
+
+use smoke_basin::parse_stdin;
+use smoke_basin::water::pools;
+
+fn main() -> Result<(),Box<dyn Error>> {
+    let map = parse_stdin()?;
+
+    let pools = pools(&map);
+    for (p, pool) in pools.iter().enumerate() {
+        let (i, j) = pool.spill;
+        let into = match pool.into {
+            Some(q) => format!("pool #{}", q),
+            None => "off the map".to_owned(),
+        };
+        println!("pool #{}: {} cells, level {}, volume {}, overflows @ ({}, {}) into {}",
+            p, pool.cells.len(), pool.level, pool.volume, i, j, into);
+    }
+    println!("total volume: {}", pools.iter().map(|p| p.volume).sum::<usize>());
+
+    Ok(())
+}
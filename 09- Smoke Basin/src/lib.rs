@@ -1,47 +1,150 @@
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt;
 use std::io::{self, BufRead};
 
-use grid;
+pub mod basins;
+pub mod render;
+pub mod water;
 
-const SIZE: usize = 100;
+pub type Height = u16;
+pub type Map = grid::Grid<Height>;
 
-type Map = grid::Grid<u8>;
+/// Which cells count as neighbors of a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Up, left, down and right.
+    Four,
+    /// Including diagonals.
+    Eight,
+}
 
-pub fn find_lowest_neighbor(map: &Map, i: usize, j: usize) -> Option<(usize, usize)> {
+/// The neighbors of a cell, within the map.
+pub fn neighbors(map: &Map, i: usize, j: usize, neighborhood: Neighborhood) -> Vec<(usize, usize)> {
     let (n, m) = map.size();
 
-    let mut neighbors: Vec<(usize, usize, u8)> = Vec::new();
-    if i > 0 {
-        neighbors.push((i-1, j, map[i-1][j]));
-    }
-    if j > 0 {
-        neighbors.push((i, j-1, map[i][j-1]));
-    }
-    if i < n-1 {
-        neighbors.push((i+1, j, map[i+1][j]));
-    }
-    if j < m-1 {
-        neighbors.push((i, j+1, map[i][j+1]));
+    let mut neighbors: Vec<(usize, usize)> = Vec::with_capacity(8);
+    let imin = if i > 0 { i-1 } else { i };
+    let imax = if i < n-1 { i+1 } else { i };
+    let jmin = if j > 0 { j-1 } else { j };
+    let jmax = if j < m-1 { j+1 } else { j };
+    for a in imin..=imax {
+        for b in jmin..=jmax {
+            let diagonal = a != i && b != j;
+            if (a, b) != (i, j) && (neighborhood == Neighborhood::Eight || !diagonal) {
+                neighbors.push((a, b));
+            }
+        }
     }
+    neighbors
+}
 
+pub fn find_lowest_neighbor(map: &Map, i: usize, j: usize, neighborhood: Neighborhood) -> Option<(usize, usize)> {
     let mut lowest = None;
     let mut level = map[i][j];
-    for neighbor in &neighbors {
-        if neighbor.2 <= level {
-            lowest = Some((neighbor.0, neighbor.1));
-            level = neighbor.2;
+    for (a, b) in neighbors(map, i, j, neighborhood) {
+        if map[a][b] <= level {
+            lowest = Some((a, b));
+            level = map[a][b];
         }
     }
 
     lowest
 }
 
-pub fn parse_stdin() -> Map {
-    let mut cells: Vec<u8> = Vec::with_capacity(SIZE*SIZE);
+/// Cells strictly lower than all their neighbors.
+pub fn low_points(map: &Map, neighborhood: Neighborhood) -> Vec<(usize, usize)> {
+    let (n, m) = map.size();
+    let mut points = Vec::new();
+    for i in 0..n {
+        for j in 0..m {
+            if find_lowest_neighbor(map, i, j, neighborhood).is_none() {
+                points.push((i, j));
+            }
+        }
+    }
+    points
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line doesn't have as many cells as the first one.
+    Ragged { line: usize, expected: usize, found: usize },
+    /// A cell isn't a valid height.
+    InvalidHeight { line: usize, cell: String },
+    Empty,
+}
 
-    let stdin = io::stdin();
-    for line in stdin.lock().lines() {
-        cells.extend(line.unwrap().into_bytes().iter().map(|n| n-0x30).collect::<Vec<u8>>());
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ragged { line, expected, found } => write!(f, "line {}: expected {} cells, found {}", line, expected, found),
+            Self::InvalidHeight { line, cell } => write!(f, "line {}: invalid height {:?}", line, cell),
+            Self::Empty => write!(f, "empty map"),
+        }
     }
+}
 
-    Map::from_vec(cells, SIZE)
+impl Error for ParseError {}
+
+pub fn parse_stdin() -> Result<Map, ParseError> {
+    parse_lines(io::stdin().lock().lines().map_while(Result::ok))
+}
+
+/// Parse a heightmap, either as rows of single digits or as rows of comma-separated heights.
+pub fn parse_lines<I>(lines: I) -> Result<Map, ParseError>
+where
+    I: IntoIterator,
+    I::Item: Borrow<str>,
+{
+    let mut cells: Vec<Height> = Vec::new();
+    let mut cols: usize = 0;
+
+    for (l, line) in lines.into_iter().enumerate() {
+        let line = line.borrow().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = |cell: &str| ParseError::InvalidHeight { line: l+1, cell: cell.to_owned() };
+
+        let row: Vec<Height> = if line.contains(',') {
+            line.split(',').map(|c| c.trim().parse().map_err(|_| invalid(c))).collect::<Result<_, _>>()?
+        } else {
+            line.chars().map(|c| c.to_digit(10).map(|d| d as Height).ok_or_else(|| invalid(&c.to_string()))).collect::<Result<_, _>>()?
+        };
+
+        if cols == 0 {
+            cols = row.len();
+        } else if row.len() != cols {
+            return Err(ParseError::Ragged { line: l+1, expected: cols, found: row.len() });
+        }
+        cells.extend(row);
+    }
+
+    if cells.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    Ok(Map::from_vec(cells, cols))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let map = parse_lines("123\n456\n".lines()).unwrap();
+        assert_eq!(map.size(), (2, 3));
+        let map = parse_lines("10,200\n3000,4\n".lines()).unwrap();
+        assert_eq!(map[1][0], 3000);
+        assert_eq!(parse_lines("123\n45\n".lines()).unwrap_err(), ParseError::Ragged { line: 2, expected: 3, found: 2 });
+        assert_eq!(parse_lines("1x3\n".lines()).unwrap_err(), ParseError::InvalidHeight { line: 1, cell: "x".to_owned() });
+    }
+
+    #[test]
+    fn low_points_neighborhood() {
+        let map = parse_lines("999\n919\n990\n".lines()).unwrap();
+        assert_eq!(low_points(&map, Neighborhood::Four), vec![(1, 1), (2, 2)]);
+        assert_eq!(low_points(&map, Neighborhood::Eight), vec![(2, 2)]);
+    }
 }
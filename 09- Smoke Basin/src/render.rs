@@ -0,0 +1,96 @@
+use std::io::{self, Write};
+
+use crate::basins::{Basins, Point};
+use crate::Map;
+
+pub type Rgb = [u8; 3];
+pub type Image = grid::Grid<Rgb>;
+
+/// Off the color wheel, and darker than any shaded cell.
+const LOW_POINT: Rgb = [0, 0, 0];
+
+/// A distinct hue for each basin, spread around the color wheel with the golden ratio.
+fn basin_color(label: usize) -> Rgb {
+    let hue = (label as f64 * 0.618_033_988_75).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
+
+/// Render the map: cells are shaded by height (darker is lower), tinted with the color of their
+/// basin. Barriers are left gray, and low points are highlighted in black.
+pub fn render(map: &Map, basins: &Basins, low_points: &[Point]) -> Image {
+    let (n, m) = map.size();
+    let max = map.iter().copied().max().unwrap_or(0).max(1) as f64;
+
+    let mut image: Image = grid::Grid::init(n, m, [0, 0, 0]);
+    for i in 0..n {
+        for j in 0..m {
+            // Keep some brightness for the lowest cells, so that their color shows.
+            let shade = 0.3 + 0.7 * map[i][j] as f64 / max;
+            let color = match basins.labels[i][j] {
+                Some(label) => basin_color(label),
+                None => [255, 255, 255],
+            };
+            image[i][j] = color.map(|c| (c as f64 * shade) as u8);
+        }
+    }
+    for &(i, j) in low_points {
+        image[i][j] = LOW_POINT;
+    }
+
+    image
+}
+
+/// Write the image as a binary PPM.
+pub fn write_ppm<W: Write>(image: &Image, out: &mut W) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.cols(), image.rows())?;
+    for pixel in image.iter() {
+        out.write_all(pixel)?;
+    }
+    Ok(())
+}
+
+/// Write the heights of the map to a terminal, on the colors of the image.
+pub fn write_ansi<W: Write>(map: &Map, image: &Image, out: &mut W) -> io::Result<()> {
+    let (n, m) = map.size();
+    let width = 1 + map.iter().max().map_or(1, |h| h.to_string().len());
+    for i in 0..n {
+        for j in 0..m {
+            let [r, g, b] = image[i][j];
+            write!(out, "\x1b[48;2;{};{};{}m{:>width$}", r, g, b, map[i][j], width = width)?;
+        }
+        writeln!(out, "\x1b[0m")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basins::segment;
+
+    #[test]
+    fn ppm() {
+        let map = Map::from_vec(vec![0, 9, 3], 3);
+        let image = render(&map, &segment(&map, 9), &[(0, 0)]);
+        assert_eq!(image[0][0], LOW_POINT);
+        assert_eq!(image[0][1], [255, 255, 255]);
+        assert_ne!(image[0][2], image[0][1]);
+        // Low points stand out even in the first basin, which is red.
+        let image = render(&map, &segment(&map, 9), &[]);
+        assert_ne!(image[0][0], LOW_POINT);
+
+        let mut out = Vec::new();
+        write_ppm(&image, &mut out).unwrap();
+        assert!(out.starts_with(b"P6\n3 1\n255\n"));
+        assert_eq!(out.len(), 11 + 3*3);
+    }
+}
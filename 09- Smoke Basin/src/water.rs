@@ -0,0 +1,163 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::basins::Point;
+use crate::{neighbors, Height, Map, Neighborhood};
+
+/// A pool of water held by the map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pool {
+    /// Submerged cells, in row-major order.
+    pub cells: Vec<Point>,
+    /// Height of the water surface.
+    pub level: Height,
+    /// Volume of water held.
+    pub volume: usize,
+    /// The lowest cell on the rim of the pool, where it overflows.
+    pub spill: Point,
+    /// The pool it overflows into, or `None` if it drains off the map.
+    pub into: Option<usize>,
+}
+
+/// Water level of each cell once the map has been filled up: water drains off the edges of the
+/// map, and fills everything else up to the lowest point of its rim.
+// Classic "trapping rain water" in 2D: flood the map from its edges, always expanding from the
+// lowest cell reached so far. A cell reached from a neighbor at level L holds water up to L.
+pub fn fill(map: &Map) -> grid::Grid<Height> {
+    let (n, m) = map.size();
+    let mut levels: grid::Grid<Option<Height>> = grid::Grid::init(n, m, None);
+    let mut queue = BinaryHeap::new();
+
+    for i in 0..n {
+        for j in 0..m {
+            if i == 0 || j == 0 || i == n-1 || j == m-1 {
+                levels[i][j] = Some(map[i][j]);
+                queue.push(Reverse((map[i][j], i, j)));
+            }
+        }
+    }
+
+    while let Some(Reverse((level, i, j))) = queue.pop() {
+        for (a, b) in neighbors(map, i, j, Neighborhood::Four) {
+            if levels[a][b].is_none() {
+                let l = level.max(map[a][b]);
+                levels[a][b] = Some(l);
+                queue.push(Reverse((l, a, b)));
+            }
+        }
+    }
+
+    grid::Grid::from_vec(levels.into_vec().into_iter().map(Option::unwrap).collect(), m)
+}
+
+/// Find all the pools of water held by the map.
+pub fn pools(map: &Map) -> Vec<Pool> {
+    let levels = fill(map);
+    let (n, m) = map.size();
+    let submerged = |i: usize, j: usize| levels[i][j] > map[i][j];
+
+    // Group submerged cells into pools.
+    let mut labels: grid::Grid<Option<usize>> = grid::Grid::init(n, m, None);
+    let mut pools: Vec<Pool> = Vec::new();
+    for i in 0..n {
+        for j in 0..m {
+            if !submerged(i, j) || labels[i][j].is_some() {
+                continue;
+            }
+
+            let label = pools.len();
+            let mut cells = Vec::new();
+            let mut rim = Vec::new();
+            let mut queue = VecDeque::from([(i, j)]);
+            labels[i][j] = Some(label);
+            while let Some((x, y)) = queue.pop_front() {
+                cells.push((x, y));
+                for (a, b) in neighbors(map, x, y, Neighborhood::Four) {
+                    if !submerged(a, b) {
+                        rim.push((a, b));
+                    } else if labels[a][b].is_none() {
+                        labels[a][b] = Some(label);
+                        queue.push_back((a, b));
+                    }
+                }
+            }
+            cells.sort_unstable();
+
+            let level = levels[i][j];
+            let volume = cells.iter().map(|&(x, y)| (level - map[x][y]) as usize).sum();
+            let spill = *rim.iter().min_by_key(|&&(x, y)| (map[x][y], x, y)).unwrap();
+            pools.push(Pool { cells, level, volume, spill, into: None });
+        }
+    }
+
+    // Let the overflow run from each spill point over cells no higher than the pool's surface,
+    // nearest first, until it reaches another pool or the edge of the map.
+    for (p, pool) in pools.iter_mut().enumerate() {
+        let mut visited: grid::Grid<bool> = grid::Grid::init(n, m, false);
+        let mut queue = VecDeque::from([pool.spill]);
+        visited[pool.spill.0][pool.spill.1] = true;
+        while let Some((i, j)) = queue.pop_front() {
+            if let Some(q) = labels[i][j].filter(|&q| q != p) {
+                pool.into = Some(q);
+                break;
+            }
+            if i == 0 || j == 0 || i == n-1 || j == m-1 {
+                break;
+            }
+            for (a, b) in neighbors(map, i, j, Neighborhood::Four) {
+                if !visited[a][b] && levels[a][b] <= pool.level {
+                    visited[a][b] = true;
+                    queue.push_back((a, b));
+                }
+            }
+        }
+    }
+
+    pools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow() {
+        let map = Map::from_vec(vec![
+            8,8,8,8,8,
+            8,1,6,3,4,
+            8,8,8,8,8,
+        ], 5);
+        let pools = pools(&map);
+        assert_eq!(pools, vec![
+            Pool { cells: vec![(1,1)], level: 6, volume: 5, spill: (1,2), into: Some(1) },
+            Pool { cells: vec![(1,3)], level: 4, volume: 1, spill: (1,4), into: None },
+        ]);
+    }
+
+    #[test]
+    fn enclosed() {
+        let map = Map::from_vec(vec![
+            7,7,7,7,7,
+            7,1,5,3,7,
+            7,7,7,7,2,
+        ], 5);
+        let pools = pools(&map);
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].level, 7);
+        assert_eq!(pools[0].volume, 6+2+4);
+    }
+
+    #[test]
+    fn plateau() {
+        // The pool on the left overflows along the plateau of 5s, past the dead end above it.
+        let map = Map::from_vec(vec![
+            9,9,9,9,9,9,9,9,9,
+            9,5,5,5,5,5,5,5,9,
+            9,9,9,1,9,9,9,1,3,
+            9,9,9,9,9,9,9,9,9,
+        ], 9);
+        let pools = pools(&map);
+        assert_eq!(pools[0], Pool { cells: vec![(2,3)], level: 5, volume: 4, spill: (1,3), into: Some(1) });
+        assert_eq!(pools[1], Pool { cells: vec![(2,7)], level: 3, volume: 2, spill: (2,8), into: None });
+    }
+}
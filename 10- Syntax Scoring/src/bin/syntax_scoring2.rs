@@ -1,26 +1,22 @@
-use std::collections::HashMap;
-
-use syntax_scoring::{TOKEN_INVERSE, Parse, parse_line, parse_stdin};
+use syntax_scoring::grammar::Grammar;
+use syntax_scoring::{Parse, parse_stdin};
 
 fn main() {
     let lines = parse_stdin();
-
-    let token_score = HashMap::from([
-        (')', 1u64),
-        (']', 2u64),
-        ('}', 3u64),
-        ('>', 4u64),
-    ]);
+    let grammar = Grammar::aoc();
 
     let mut scores: Vec<u64> = Vec::new();
 
     for (i, line) in lines.iter().enumerate() {
-        let parse = parse_line(&line, i);
-        if let Parse::Incomplete(chunks) = parse {
-            let completion: Vec<_> = chunks.iter().rev().map(|c| *TOKEN_INVERSE.get(c).unwrap()).collect();
-            let score = completion.iter().fold(0, |score, c| score * 5 + *token_score.get(c).unwrap());
-            println!("Completed line {} with \"{}\" (score: {}).", i, completion.iter().collect::<String>(), score);
-            scores.push(score);
+        match grammar.parse_line(line) {
+            Parse::Incomplete(chunks) => {
+                let completion = grammar.completion(&chunks);
+                let score = grammar.completion_score(&completion);
+                println!("Completed line {} with \"{}\" (score: {}).", i, completion.iter().collect::<String>(), score);
+                scores.push(score);
+            },
+            Parse::Invalid(c) => eprintln!("Invalid character on line {}: '{}'.", i, c),
+            _ => (),
         }
     }
 
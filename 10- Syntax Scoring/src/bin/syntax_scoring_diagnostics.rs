@@ -0,0 +1,12 @@
+use syntax_scoring::grammar::Grammar;
+use syntax_scoring::parse_stdin;
+use syntax_scoring::repair::repair;
+
+fn main() {
+    let lines = parse_stdin();
+    let grammar = Grammar::aoc();
+
+    for (i, line) in lines.iter().enumerate() {
+        print!("{}", repair(&grammar, line).to_json(i + 1));
+    }
+}
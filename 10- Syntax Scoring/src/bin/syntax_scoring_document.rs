@@ -0,0 +1,29 @@
+use syntax_scoring::document::parse_document;
+use syntax_scoring::grammar::Grammar;
+use syntax_scoring::{Parse, parse_stdin};
+
+fn main() {
+    let lines = parse_stdin();
+    let grammar = Grammar::aoc();
+
+    let document = parse_document(&grammar, &lines);
+    match &document.parse {
+        Parse::Ok => println!("Document is valid."),
+        Parse::SyntaxError(c, expected) => {
+            let position = document.error.unwrap();
+            if let Some(expected) = expected {
+                println!("Syntax error at {}: found '{}', expected '{}'.", position, c, expected);
+            } else {
+                println!("Syntax error at {}: found '{}' with no open chunk.", position, c);
+            }
+        },
+        Parse::Invalid(c) => println!("Invalid character at {}: '{}'.", document.error.unwrap(), c),
+        Parse::Incomplete(chunks) => {
+            for (position, c) in &document.unclosed {
+                println!("Unclosed '{}' opened at {}.", c, position);
+            }
+            let completion = grammar.completion(chunks);
+            println!("Completed document with \"{}\" (score: {}).", completion.iter().collect::<String>(), grammar.completion_score(&completion));
+        },
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::grammar::{Grammar, Token};
+use crate::Parse;
+
+/// 1-based line and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The result of checking a whole document.
+pub struct Document {
+    pub parse: Parse,
+    /// Where the syntax error or invalid character is.
+    pub error: Option<Position>,
+    /// Chunks left open at the end of the document (or at the error), outermost first.
+    pub unclosed: Vec<(Position, char)>,
+}
+
+/// Check a document, with chunks spanning across lines. String literals don't span lines: one
+/// left open is closed at the end of its line.
+pub fn parse_document<S: AsRef<str>>(grammar: &Grammar, lines: &[S]) -> Document {
+    let mut chunks: Vec<(Position, char)> = Vec::new();
+
+    for (l, line) in lines.iter().enumerate() {
+        for (column, token) in grammar.tokens(line.as_ref()) {
+            let position = Position { line: l+1, column: column+1 };
+            let error = match token {
+                Token::Open(c) => {
+                    chunks.push((position, c));
+                    continue;
+                },
+                Token::Close(c) => match chunks.last() {
+                    Some(&(_, last)) => {
+                        let expected = grammar.inverse(last).unwrap();
+                        if c == expected {
+                            chunks.pop();
+                            continue;
+                        }
+                        Parse::SyntaxError(c, Some(expected))
+                    },
+                    None => Parse::SyntaxError(c, None),
+                },
+                Token::LiteralStart(_) | Token::LiteralEnd(_) => continue,
+                Token::Invalid(c) => Parse::Invalid(c),
+            };
+            return Document { parse: error, error: Some(position), unclosed: chunks };
+        }
+    }
+
+    let parse = if chunks.is_empty() {
+        Parse::Ok
+    } else {
+        Parse::Incomplete(chunks.iter().map(|&(_, c)| c).collect())
+    };
+    Document { parse, error: None, unclosed: chunks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiline() {
+        let grammar = Grammar { ignore_whitespace: true, ignore_identifiers: true, ..Grammar::aoc() };
+        let lines = [
+            "fn main() {",
+            "    if x {",
+            "        (a)",
+            "    }",
+            "    [b",
+        ];
+        let document = parse_document(&grammar, &lines);
+        match document.parse {
+            Parse::Incomplete(chunks) => assert_eq!(grammar.completion(&chunks), vec![']', '}']),
+            _ => panic!("document should be incomplete"),
+        }
+        assert_eq!(document.unclosed, vec![
+            (Position { line: 1, column: 11 }, '{'),
+            (Position { line: 5, column: 5 }, '['),
+        ]);
+
+        let document = parse_document(&grammar, &["{", "(}"]);
+        assert!(matches!(document.parse, Parse::SyntaxError('}', Some(')'))));
+        assert_eq!(document.error, Some(Position { line: 2, column: 2 }));
+    }
+}
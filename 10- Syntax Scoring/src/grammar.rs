@@ -0,0 +1,211 @@
+use crate::Parse;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Token {
+    Open(char),
+    Close(char),
+    LiteralStart(char),
+    LiteralEnd(char),
+    Invalid(char),
+}
+
+/// A pair of chunk delimiters.
+#[derive(Clone, Debug)]
+pub struct Pair {
+    pub open: char,
+    pub close: char,
+    /// Score of an unexpected closer.
+    pub error_score: u64,
+    /// Score of a closer added to complete a line.
+    pub completion_score: u64,
+}
+
+/// A string literal, in which delimiters are ignored.
+#[derive(Clone, Debug)]
+pub struct Literal {
+    pub delimiter: char,
+    /// Character that escapes the next one, including the delimiter.
+    pub escape: Option<char>,
+}
+
+/// The syntax of chunks.
+#[derive(Clone, Debug)]
+pub struct Grammar {
+    pub pairs: Vec<Pair>,
+    /// Characters ignored outside of string literals.
+    pub ignore: Vec<char>,
+    pub ignore_whitespace: bool,
+    /// Ignore alphanumeric characters and underscores.
+    pub ignore_identifiers: bool,
+    pub literals: Vec<Literal>,
+    /// Prefix of comments running until the end of the line.
+    pub line_comment: Option<String>,
+}
+
+impl Grammar {
+    /// The grammar from the puzzle: four kinds of brackets, and nothing else.
+    pub fn aoc() -> Self {
+        let pair = |open, close, error_score, completion_score| Pair { open, close, error_score, completion_score };
+        Grammar {
+            pairs: vec![
+                pair('(', ')', 3, 1),
+                pair('[', ']', 57, 2),
+                pair('{', '}', 1197, 3),
+                pair('<', '>', 25137, 4),
+            ],
+            ignore: Vec::new(),
+            ignore_whitespace: false,
+            ignore_identifiers: false,
+            literals: Vec::new(),
+            line_comment: None,
+        }
+    }
+
+    pub(crate) fn opener(&self, c: char) -> Option<&Pair> {
+        self.pairs.iter().find(|p| p.open == c)
+    }
+
+    pub(crate) fn closer(&self, c: char) -> Option<&Pair> {
+        self.pairs.iter().find(|p| p.close == c)
+    }
+
+    fn literal(&self, c: char) -> Option<&Literal> {
+        self.literals.iter().find(|l| l.delimiter == c)
+    }
+
+    fn ignored(&self, c: char) -> bool {
+        self.ignore.contains(&c)
+            || (self.ignore_whitespace && c.is_whitespace())
+            || (self.ignore_identifiers && (c.is_alphanumeric() || c == '_'))
+    }
+
+    /// The character that closes a chunk (or a string literal).
+    pub fn inverse(&self, open: char) -> Option<char> {
+        self.opener(open).map(|p| p.close).or_else(|| self.literal(open).map(|l| l.delimiter))
+    }
+
+    /// Split a line into tokens, with their (0-based) column. Ignored characters, the contents of
+    /// string literals and comments are skipped.
+    pub(crate) fn tokens(&self, line: &str) -> Vec<(usize, Token)> {
+        let mut tokens = Vec::new();
+        // The literal we're in, if any.
+        let mut literal: Option<&Literal> = None;
+        let mut escaped = false;
+
+        for (col, (i, c)) in line.char_indices().enumerate() {
+            if let Some(l) = literal {
+                if escaped {
+                    escaped = false;
+                } else if Some(c) == l.escape {
+                    escaped = true;
+                } else if c == l.delimiter {
+                    tokens.push((col, Token::LiteralEnd(c)));
+                    literal = None;
+                }
+                continue;
+            }
+
+            if let Some(comment) = &self.line_comment {
+                if line[i..].starts_with(comment.as_str()) {
+                    break;
+                }
+            }
+
+            if self.opener(c).is_some() {
+                tokens.push((col, Token::Open(c)));
+            } else if self.closer(c).is_some() {
+                tokens.push((col, Token::Close(c)));
+            } else if let Some(l) = self.literal(c) {
+                tokens.push((col, Token::LiteralStart(c)));
+                literal = Some(l);
+            } else if !self.ignored(c) {
+                tokens.push((col, Token::Invalid(c)));
+            }
+        }
+
+        tokens
+    }
+
+    pub fn parse_line(&self, line: &str) -> Parse {
+        let mut chunks: Vec<char> = Vec::new();
+        for (_, token) in self.tokens(line) {
+            match token {
+                Token::Open(c) | Token::LiteralStart(c) => chunks.push(c),
+                Token::LiteralEnd(_) => { chunks.pop(); },
+                Token::Close(c) => match chunks.pop() {
+                    Some(last) => {
+                        let expected = self.inverse(last).unwrap();
+                        if c != expected {
+                            return Parse::SyntaxError(c, Some(expected));
+                        }
+                    },
+                    None => return Parse::SyntaxError(c, None),
+                },
+                Token::Invalid(c) => return Parse::Invalid(c),
+            }
+        }
+
+        if chunks.is_empty() {
+            Parse::Ok
+        } else {
+            Parse::Incomplete(chunks)
+        }
+    }
+
+    /// Score of a syntax error on an unexpected closer.
+    pub fn error_score(&self, c: char) -> u64 {
+        self.closer(c).map_or(0, |p| p.error_score)
+    }
+
+    /// The closers that complete the open chunks, innermost first.
+    pub fn completion(&self, chunks: &[char]) -> Vec<char> {
+        chunks.iter().rev().map(|&c| self.inverse(c).unwrap()).collect()
+    }
+
+    pub fn completion_score(&self, completion: &[char]) -> u64 {
+        completion.iter().fold(0, |score, &c| score * 5 + self.closer(c).map_or(0, |p| p.completion_score))
+    }
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::aoc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aoc() {
+        let grammar = Grammar::aoc();
+        assert!(matches!(grammar.parse_line("{([(<{}[<>[]}>{[]{[(<()>"), Parse::SyntaxError('}', Some(']'))));
+        match grammar.parse_line("[({(<(())[]>[[{[]{<()<>>") {
+            Parse::Incomplete(chunks) => {
+                let completion = grammar.completion(&chunks);
+                assert_eq!(completion.iter().collect::<String>(), "}}]])})]");
+                assert_eq!(grammar.completion_score(&completion), 288957);
+            },
+            _ => panic!("line should be incomplete"),
+        }
+        assert!(matches!(grammar.parse_line("(a)"), Parse::Invalid('a')));
+    }
+
+    #[test]
+    fn literals_and_comments() {
+        let grammar = Grammar {
+            ignore_whitespace: true,
+            ignore_identifiers: true,
+            ignore: vec![';', ','],
+            literals: vec![Literal { delimiter: '"', escape: Some('\\') }],
+            line_comment: Some("//".to_owned()),
+            ..Grammar::aoc()
+        };
+        assert!(matches!(grammar.parse_line(r#"f(a, "(\")") { g[0]; } // )"#), Parse::Ok));
+        match grammar.parse_line(r#"f("[ \" "#) {
+            Parse::Incomplete(chunks) => assert_eq!(grammar.completion(&chunks), vec!['"', ')']),
+            _ => panic!("line should be incomplete"),
+        }
+    }
+}
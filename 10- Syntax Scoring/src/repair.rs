@@ -0,0 +1,281 @@
+use std::fmt::Write;
+
+use crate::grammar::{Grammar, Token};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditKind {
+    Insert(char),
+    Delete(char),
+    /// Replace the first character with the second one.
+    Substitute(char, char),
+}
+
+/// A single-character edit. Insertions go before the character at `column`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edit {
+    /// 1-based column, in characters.
+    pub column: usize,
+    pub kind: EditKind,
+}
+
+impl Edit {
+    pub fn message(&self) -> String {
+        match self.kind {
+            EditKind::Insert(c) => format!("missing '{}'", c),
+            EditKind::Delete(c) => format!("unexpected '{}'", c),
+            EditKind::Substitute(found, expected) => format!("expected '{}', found '{}'", expected, found),
+        }
+    }
+}
+
+/// A minimal set of edits that fixes a line, and the line once fixed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repair {
+    pub edits: Vec<Edit>,
+    pub corrected: String,
+}
+
+impl Repair {
+    /// One JSON diagnostic per edit, one per line.
+    pub fn to_json(&self, line: usize) -> String {
+        let mut json = String::new();
+        for edit in &self.edits {
+            let (kind, text) = match edit.kind {
+                EditKind::Insert(c) => ("insert", c.to_string()),
+                EditKind::Delete(_) => ("delete", String::new()),
+                EditKind::Substitute(_, c) => ("substitute", c.to_string()),
+            };
+            writeln!(json,
+                r#"{{"line":{},"column":{},"severity":"error","message":{},"edit":{{"kind":"{}","text":{}}},"corrected":{}}}"#,
+                line, edit.column, json_string(&edit.message()), kind, json_string(&text), json_string(&self.corrected)
+            ).unwrap();
+        }
+        json
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// How a range of brackets was made balanced.
+#[derive(Clone, Copy)]
+enum Choice {
+    Empty,
+    /// The first and last brackets are a pair, after substituting some of them.
+    Pair,
+    /// The first bracket has no partner.
+    LoneFirst,
+    /// The last bracket has no partner.
+    LoneLast,
+    /// Two balanced ranges, split at the given index.
+    Split(usize),
+}
+
+struct Solver<'a> {
+    grammar: &'a Grammar,
+    /// (column, bracket)
+    brackets: Vec<(usize, char)>,
+    /// Column after the end of the line.
+    end: usize,
+    cost: Vec<Vec<usize>>,
+    choice: Vec<Vec<Choice>>,
+}
+
+impl Solver<'_> {
+    fn is_open(&self, c: char) -> bool {
+        self.grammar.opener(c).is_some()
+    }
+
+    /// Substitutions needed to make the brackets a pair, if at most one is needed.
+    fn pair_edits(&self, first: char, last: char) -> Option<Vec<(usize, char)>> {
+        match (self.is_open(first), self.is_open(last)) {
+            (true, false) if self.grammar.inverse(first) == Some(last) => Some(vec![]),
+            (true, _) => Some(vec![(1, self.grammar.inverse(first).unwrap())]),
+            (false, false) => Some(vec![(0, self.grammar.closer(last).unwrap().open)]),
+            (false, true) => None,
+        }
+    }
+
+    // Interval DP: cost[i][j] is the minimal number of edits making brackets[i..j] balanced.
+    fn solve(&mut self) {
+        let n = self.brackets.len();
+        self.cost = vec![vec![0; n+1]; n+1];
+        self.choice = vec![vec![Choice::Empty; n+1]; n+1];
+
+        for len in 1..=n {
+            for i in 0..=n-len {
+                let j = i + len;
+                let mut best = (1 + self.cost[i+1][j], Choice::LoneFirst);
+                if 1 + self.cost[i][j-1] < best.0 {
+                    best = (1 + self.cost[i][j-1], Choice::LoneLast);
+                }
+                if len >= 2 {
+                    if let Some(edits) = self.pair_edits(self.brackets[i].1, self.brackets[j-1].1) {
+                        if edits.len() + self.cost[i+1][j-1] <= best.0 {
+                            best = (edits.len() + self.cost[i+1][j-1], Choice::Pair);
+                        }
+                    }
+                    for k in i+1..j {
+                        if self.cost[i][k] + self.cost[k][j] < best.0 {
+                            best = (self.cost[i][k] + self.cost[k][j], Choice::Split(k));
+                        }
+                    }
+                }
+                self.cost[i][j] = best.0;
+                self.choice[i][j] = best.1;
+            }
+        }
+    }
+
+    fn column(&self, i: usize) -> usize {
+        self.brackets.get(i).map_or(self.end, |b| b.0)
+    }
+
+    /// Collect the edits for brackets[i..j]. Insertions at the same column are kept in order.
+    fn edits(&self, i: usize, j: usize, edits: &mut Vec<Edit>) {
+        match self.choice[i][j] {
+            Choice::Empty => (),
+            Choice::Pair => {
+                let (first, last) = (self.brackets[i], self.brackets[j-1]);
+                for (which, c) in self.pair_edits(first.1, last.1).unwrap() {
+                    let (column, found) = if which == 0 { first } else { last };
+                    edits.push(Edit { column, kind: EditKind::Substitute(found, c) });
+                }
+                self.edits(i+1, j-1, edits);
+            },
+            Choice::LoneFirst => {
+                let (column, c) = self.brackets[i];
+                if self.is_open(c) {
+                    // Close it at the end of the range, after the inner closers.
+                    self.edits(i+1, j, edits);
+                    edits.push(Edit { column: self.column(j), kind: EditKind::Insert(self.grammar.inverse(c).unwrap()) });
+                } else {
+                    edits.push(Edit { column, kind: EditKind::Delete(c) });
+                    self.edits(i+1, j, edits);
+                }
+            },
+            Choice::LoneLast => {
+                let (column, c) = self.brackets[j-1];
+                if self.is_open(c) {
+                    self.edits(i, j-1, edits);
+                    edits.push(Edit { column, kind: EditKind::Delete(c) });
+                } else {
+                    // Open it at the start of the range, before the inner openers.
+                    edits.push(Edit { column: self.column(i), kind: EditKind::Insert(self.grammar.closer(c).unwrap().open) });
+                    self.edits(i, j-1, edits);
+                }
+            },
+            Choice::Split(k) => {
+                self.edits(i, k, edits);
+                self.edits(k, j, edits);
+            },
+        }
+    }
+}
+
+/// Find a minimal set of insertions, deletions and substitutions of brackets that makes a line
+/// valid. Invalid characters are deleted, and unterminated string literals are closed.
+pub fn repair(grammar: &Grammar, line: &str) -> Repair {
+    let tokens = grammar.tokens(line);
+    let end = line.chars().count();
+
+    let mut edits = Vec::new();
+    let mut brackets = Vec::new();
+    let mut literal = None;
+    for (column, token) in tokens {
+        match token {
+            Token::Open(c) | Token::Close(c) => brackets.push((column, c)),
+            Token::LiteralStart(c) => literal = Some(c),
+            Token::LiteralEnd(_) => literal = None,
+            Token::Invalid(c) => edits.push(Edit { column, kind: EditKind::Delete(c) }),
+        }
+    }
+    // Nothing follows an unterminated literal, so it must be closed before anything else.
+    if let Some(c) = literal {
+        edits.push(Edit { column: end, kind: EditKind::Insert(c) });
+    }
+
+    let mut solver = Solver { grammar, brackets, end, cost: vec![], choice: vec![] };
+    solver.solve();
+    solver.edits(0, solver.brackets.len(), &mut edits);
+    // Sort by column, keeping the order of insertions at the same column. Insertions go before
+    // the edited character.
+    edits.sort_by_key(|e| (e.column, !matches!(e.kind, EditKind::Insert(_))));
+
+    // Apply the edits.
+    let mut corrected = String::with_capacity(line.len() + edits.len());
+    let mut e = 0;
+    for (column, c) in line.chars().map(Some).chain(std::iter::once(None)).enumerate() {
+        let at = edits[e..].iter().take_while(|edit| edit.column == column).count();
+        let here = &edits[e..e+at];
+        e += at;
+
+        for edit in here {
+            if let EditKind::Insert(i) = edit.kind {
+                corrected.push(i);
+            }
+        }
+        match here.iter().find(|edit| !matches!(edit.kind, EditKind::Insert(_))) {
+            Some(Edit { kind: EditKind::Substitute(_, s), .. }) => corrected.push(*s),
+            Some(_) => (),
+            None => corrected.extend(c),
+        }
+    }
+
+    // Report 1-based columns.
+    for edit in &mut edits {
+        edit.column += 1;
+    }
+
+    Repair { edits, corrected }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parse;
+
+    fn check(line: &str, edits: usize) -> Repair {
+        let grammar = Grammar::aoc();
+        let repair = repair(&grammar, line);
+        assert_eq!(repair.edits.len(), edits, "{:?}", repair);
+        assert!(matches!(grammar.parse_line(&repair.corrected), Parse::Ok), "{:?}", repair);
+        repair
+    }
+
+    #[test]
+    fn single_edits() {
+        let r = check("(]", 1);
+        assert_eq!(r.edits, vec![Edit { column: 2, kind: EditKind::Substitute(']', ')') }]);
+        let r = check("())", 1);
+        assert_eq!(r.corrected, "()");
+        let r = check("(<>", 1);
+        assert_eq!(r.corrected, "(<>)");
+        let r = check("(x)", 1);
+        assert_eq!(r.edits, vec![Edit { column: 2, kind: EditKind::Delete('x') }]);
+    }
+
+    #[test]
+    fn aoc() {
+        check("{([(<{}[<>[]}>{[]{[(<()>", 5);
+        check("[({(<(())[]>[[{[]{<()<>>", 4);
+    }
+
+    #[test]
+    fn json() {
+        let r = check("(]", 1);
+        assert_eq!(r.to_json(3),
+            "{\"line\":3,\"column\":2,\"severity\":\"error\",\"message\":\"expected ')', found ']'\",\"edit\":{\"kind\":\"substitute\",\"text\":\")\"},\"corrected\":\"()\"}\n");
+    }
+}
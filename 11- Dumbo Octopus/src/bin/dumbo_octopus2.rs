@@ -1,13 +1,18 @@
+use std::error::Error;
+
 use dumbo_octopus::parse_stdin;
+use dumbo_octopus::cycle::Cycle;
+
+const LIMIT: usize = 1_000_000;
 
-fn main() {
-    let mut octopuses = parse_stdin();
+fn main() -> Result<(),Box<dyn Error>> {
+    let octopuses = parse_stdin()?;
 
-    for i in 1..usize::MAX {
-        octopuses.step();
-        if octopuses.energy() == 0 {
-            println!("synchronized flash @ {}", i);
-            break;
-        }
+    match Cycle::find(&octopuses, LIMIT).map(|cycle| cycle.first_sync()) {
+        Some(Some(step)) => println!("synchronized flash @ {}", step),
+        Some(None) => println!("never synchronized"),
+        None => println!("no cycle within {} steps", LIMIT),
     }
+
+    Ok(())
 }
@@ -0,0 +1,27 @@
+use std::env;
+use std::error::Error;
+
+use dumbo_octopus::parse_stdin;
+use dumbo_octopus::cycle::Cycle;
+
+const LIMIT: usize = 1_000_000;
+
+/// Usage: dumbo_octopus_cycle [STEPS]
+fn main() -> Result<(),Box<dyn Error>> {
+    let steps: u64 = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 1_000_000_000_000,
+    };
+    let octopuses = parse_stdin()?;
+
+    let cycle = Cycle::find(&octopuses, LIMIT).ok_or(format!("no cycle within {} steps", LIMIT))?;
+    println!("pre-period: {}", cycle.start);
+    println!("period: {}", cycle.period);
+    match cycle.first_sync() {
+        Some(step) => println!("synchronized flash @ {}", step),
+        None => println!("never synchronized"),
+    }
+    println!("flashes after {} steps: {}", steps, cycle.flashes_after(steps));
+
+    Ok(())
+}
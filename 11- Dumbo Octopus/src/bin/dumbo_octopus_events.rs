@@ -0,0 +1,24 @@
+use std::env;
+use std::error::Error;
+use std::io;
+
+use dumbo_octopus::events::write_jsonl;
+use dumbo_octopus::parse_stdin;
+
+/// Usage: dumbo_octopus_events [STEPS]
+fn main() -> Result<(),Box<dyn Error>> {
+    let steps: usize = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 100,
+    };
+    let mut octopuses = parse_stdin()?;
+
+    octopuses.record();
+    let mut out = io::stdout().lock();
+    for _ in 0..steps {
+        octopuses.step();
+        write_jsonl(&octopuses.take_log(), &mut out)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::env;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use dumbo_octopus::parse_stdin;
+use dumbo_octopus::render::{render, write_ansi, write_ppm};
+
+const FRAME: Duration = Duration::from_millis(100);
+
+/// Usage: dumbo_octopus_frames [STEPS] [DIR]
+///
+/// Without a directory, the frames are animated on the terminal.
+fn main() -> Result<(),Box<dyn Error>> {
+    let steps: usize = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 100,
+    };
+    let dir = env::args().nth(2);
+    let mut octopuses = parse_stdin()?;
+
+    if let Some(dir) = &dir {
+        fs::create_dir_all(dir)?;
+    }
+    let mut out = io::stdout().lock();
+    for _ in 0..steps {
+        let flashes = octopuses.step();
+        match &dir {
+            Some(dir) => {
+                let path = Path::new(dir).join(format!("step{:06}.ppm", octopuses.steps()));
+                let mut file = BufWriter::new(File::create(path)?);
+                write_ppm(&render(&octopuses), &mut file)?;
+                file.flush()?;
+            },
+            None => {
+                // Clear the screen, and draw from the top.
+                write!(out, "\x1b[2J\x1b[H")?;
+                write_ansi(&octopuses, &mut out)?;
+                writeln!(out, "step {}: {} flashes", octopuses.steps(), flashes)?;
+                out.flush()?;
+                thread::sleep(FRAME);
+            },
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use crate::Octopuses;
+
+/// The octopuses' states eventually repeat: after `start` steps, they go through the same
+/// `period` states forever.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Steps before the cycle starts (the pre-period).
+    pub start: usize,
+    pub period: usize,
+    /// Flashes during each step, from the first one until the end of the first cycle.
+    flashes: Vec<u32>,
+    octopuses: usize,
+}
+
+impl Cycle {
+    /// Step copies of the octopuses until a state repeats. Gives up after `limit` steps.
+    pub fn find(octopuses: &Octopuses, limit: usize) -> Option<Cycle> {
+        let mut octopuses = octopuses.clone();
+        octopuses.log = None;
+        let mut seen: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut flashes = Vec::new();
+
+        for step in 0..=limit {
+            let state: Vec<u8> = octopuses.grid.iter().copied().collect();
+            if let Some(&start) = seen.get(&state) {
+                return Some(Cycle { start, period: step - start, flashes, octopuses: octopuses.len() });
+            }
+            seen.insert(state, step);
+            flashes.push(octopuses.step());
+        }
+
+        None
+    }
+
+    /// Flashes during the given step, counting from 1.
+    pub fn flashes_during(&self, step: u64) -> u32 {
+        assert!(step > 0, "steps count from 1");
+        let i = (step - 1) as u128;
+        let (start, period) = (self.start as u128, self.period as u128);
+        let i = if i < start { i } else { start + (i - start) % period };
+        self.flashes[i as usize]
+    }
+
+    /// Total flashes after the given number of steps.
+    pub fn flashes_after(&self, steps: u64) -> u128 {
+        let sum = |flashes: &[u32]| flashes.iter().map(|&f| f as u128).sum::<u128>();
+
+        let steps = steps as u128;
+        let (start, period) = (self.start as u128, self.period as u128);
+        if steps <= start + period {
+            return sum(&self.flashes[..steps as usize]);
+        }
+        let (cycles, rest) = ((steps - start) / period, (steps - start) % period);
+        sum(&self.flashes[..self.start])
+            + cycles * sum(&self.flashes[self.start..])
+            + sum(&self.flashes[self.start..self.start + rest as usize])
+    }
+
+    /// The first step during which all octopuses flash, if that ever happens.
+    pub fn first_sync(&self) -> Option<usize> {
+        self.flashes.iter().position(|&f| f as usize == self.octopuses).map(|i| i+1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+    use crate::{parse_lines, Neighborhood, Rules};
+
+    #[test]
+    fn puzzle() {
+        let octopuses = parse_lines(EXAMPLE.lines()).unwrap();
+        let cycle = Cycle::find(&octopuses, 1000).unwrap();
+        assert_eq!(cycle.first_sync(), Some(195));
+        assert_eq!(cycle.flashes_after(100), 1656);
+        // Once synchronized, they all flash every 10 steps.
+        assert_eq!(cycle.period, 10);
+
+        let mut stepped = octopuses.clone();
+        let total: u128 = (1..=cycle.start as u64 + 25).map(|s| {
+            let flashes = stepped.step();
+            assert_eq!(cycle.flashes_during(s), flashes);
+            flashes as u128
+        }).sum();
+        assert_eq!(cycle.flashes_after(cycle.start as u64 + 25), total);
+        let k = 10u64.pow(11);
+        assert_eq!(cycle.flashes_after(cycle.start as u64 + 10*k), cycle.flashes_after(cycle.start as u64) + 100 * k as u128);
+    }
+
+    #[test]
+    fn never_synchronized() {
+        // The two octopuses take turns flashing, 9 steps apart from each other.
+        let mut octopuses = parse_lines("05".lines()).unwrap();
+        octopuses.set_rules(Rules { neighborhood: Neighborhood::Four, ..Rules::puzzle() });
+        let cycle = Cycle::find(&octopuses, 1000).unwrap();
+        assert_eq!((cycle.start, cycle.period), (0, 9));
+        assert_eq!(cycle.first_sync(), None);
+        assert_eq!(cycle.flashes_after(10u64.pow(12)), 2 * (10u128.pow(12) / 9));
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::{self, Write};
+
+/// A flash, as recorded in the event log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Flash {
+    /// Step during which the octopus flashed, counting from 1.
+    pub step: usize,
+    pub row: usize,
+    pub col: usize,
+    /// 0 for octopuses flashing from the step's own increase, and one more than their trigger's
+    /// for the others.
+    pub generation: u32,
+    /// The neighbor whose flash took this octopus over the threshold.
+    pub trigger: Option<(usize, usize)>,
+}
+
+impl Flash {
+    pub fn to_json(&self) -> String {
+        let trigger = match self.trigger {
+            Some((row, col)) => format!("[{},{}]", row, col),
+            None => "null".to_owned(),
+        };
+        format!(r#"{{"step":{},"row":{},"col":{},"generation":{},"trigger":{}}}"#,
+            self.step, self.row, self.col, self.generation, trigger)
+    }
+}
+
+/// Write one JSON object per flash, one per line.
+pub fn write_jsonl<W: Write>(flashes: &[Flash], out: &mut W) -> io::Result<()> {
+    for flash in flashes {
+        writeln!(out, "{}", flash.to_json())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_lines, Neighborhood, Rules};
+
+    #[test]
+    fn cascade() {
+        let mut octopuses = parse_lines("9880".lines()).unwrap();
+        octopuses.set_rules(Rules { neighborhood: Neighborhood::Four, ..Rules::puzzle() });
+        octopuses.record();
+        assert_eq!(octopuses.step(), 3);
+        assert_eq!(octopuses.log(), &[
+            Flash { step: 1, row: 0, col: 0, generation: 0, trigger: None },
+            Flash { step: 1, row: 0, col: 1, generation: 1, trigger: Some((0, 0)) },
+            Flash { step: 1, row: 0, col: 2, generation: 2, trigger: Some((0, 1)) },
+        ]);
+
+        let mut out = Vec::new();
+        write_jsonl(&octopuses.take_log(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().nth(1), Some(r#"{"step":1,"row":0,"col":1,"generation":1,"trigger":[0,0]}"#));
+        assert!(octopuses.log().is_empty());
+    }
+}
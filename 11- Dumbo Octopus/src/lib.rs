@@ -1,17 +1,117 @@
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt;
 use std::io::{self, BufRead};
 
-use grid;
+pub mod cycle;
+pub mod events;
+pub mod render;
 
-const SIZE: usize = 10;
+use events::Flash;
 
+/// Which octopuses are adjacent to each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Up, left, down and right.
+    Four,
+    /// Including diagonals.
+    Eight,
+}
+
+/// How octopuses gain energy and flash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// An octopus flashes when its energy goes above this level.
+    pub threshold: u8,
+    /// Energy of an octopus after it flashed.
+    pub reset: u8,
+    pub neighborhood: Neighborhood,
+    /// Whether the grid wraps around its edges.
+    pub wrap: bool,
+}
+
+impl Rules {
+    pub fn puzzle() -> Self {
+        Rules {
+            threshold: 9,
+            reset: 0,
+            neighborhood: Neighborhood::Eight,
+            wrap: false,
+        }
+    }
+
+    fn check(&self) {
+        assert!(self.reset <= self.threshold, "reset energy above the flash threshold");
+        // An octopus gains at most 1 from the step and 1 from each neighbor before it flashes.
+        assert!(self.threshold <= u8::MAX - 9, "flash threshold too high");
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::puzzle()
+    }
+}
+
+#[derive(Clone)]
 pub struct Octopuses {
     grid: grid::Grid<u8>,
+    rules: Rules,
+    steps: usize,
+    /// Which octopuses flashed during the last step.
+    flashed: grid::Grid<bool>,
+    /// The flashes so far, if they are recorded.
+    log: Option<Vec<Flash>>,
 }
 
 impl Octopuses {
+    pub fn new(grid: grid::Grid<u8>, rules: Rules) -> Self {
+        rules.check();
+        let flashed = grid::Grid::init(grid.rows(), grid.cols(), false);
+        Octopuses { grid, rules, steps: 0, flashed, log: None }
+    }
+
+    pub fn rules(&self) -> &Rules { &self.rules }
+
+    pub fn set_rules(&mut self, rules: Rules) {
+        rules.check();
+        self.rules = rules;
+    }
+
+    pub fn size(&self) -> (usize, usize) { self.grid.size() }
+
+    pub fn len(&self) -> usize { self.grid.rows() * self.grid.cols() }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Steps taken so far.
+    pub fn steps(&self) -> usize { self.steps }
+
+    pub fn energy_at(&self, row: usize, col: usize) -> u8 { self.grid[row][col] }
+
+    /// Whether the octopus flashed during the last step.
+    pub fn flashed(&self, row: usize, col: usize) -> bool { self.flashed[row][col] }
+
+    /// Start recording every flash from now on.
+    pub fn record(&mut self) {
+        self.log.get_or_insert_with(Vec::new);
+    }
+
+    /// The flashes recorded so far.
+    pub fn log(&self) -> &[Flash] {
+        self.log.as_deref().unwrap_or(&[])
+    }
+
+    /// Take the flashes recorded so far, and keep recording.
+    pub fn take_log(&mut self) -> Vec<Flash> {
+        self.log.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     pub fn step(&mut self) -> u32 {
         let mut flashes = 0u32;
         let (n, m) = self.grid.size();
+        self.steps += 1;
+        self.flashed.iter_mut().for_each(|f| *f = false);
 
         // 1: increase
         for i in 0..n {
@@ -23,39 +123,77 @@ impl Octopuses {
         // 2: flash
         for i in 0..n {
             for j in 0..m {
-                flashes += self.flash(i, j);
+                flashes += self.flash(i, j, None);
             }
         }
 
         flashes
     }
 
-    #[cfg(feature = "norecurse")]
-    fn flash(&mut self, row: usize, col: usize) -> u32 {
+    fn neighbors(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
         let (n, m) = self.grid.size();
+        let (n, m) = (n as isize, m as isize);
+
+        let mut neighbors = Vec::with_capacity(8);
+        for di in -1..=1isize {
+            for dj in -1..=1isize {
+                if (di == 0 && dj == 0) || (self.rules.neighborhood == Neighborhood::Four && di != 0 && dj != 0) {
+                    continue;
+                }
+                let (mut i, mut j) = (row as isize + di, col as isize + dj);
+                if self.rules.wrap {
+                    i = i.rem_euclid(n);
+                    j = j.rem_euclid(m);
+                } else if i < 0 || i >= n || j < 0 || j >= m {
+                    continue;
+                }
+                let neighbor = (i as usize, j as usize);
+                // Tiny wrapping grids can see the same neighbor several times, or themselves.
+                if neighbor != (row, col) && !neighbors.contains(&neighbor) {
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Reset the energy of a flashing octopus, and record its flash.
+    fn reset(&mut self, row: usize, col: usize, trigger: Option<&Flash>) -> Flash {
+        self.grid[row][col] = self.rules.reset;
+        self.flashed[row][col] = true;
 
+        let flash = Flash {
+            step: self.steps,
+            row,
+            col,
+            generation: trigger.map_or(0, |t| t.generation + 1),
+            trigger: trigger.map(|t| (t.row, t.col)),
+        };
+        if let Some(log) = &mut self.log {
+            log.push(flash.clone());
+        }
+        flash
+    }
+
+    // An octopus is only flashed by a neighbor when that neighbor's energy takes it over the
+    // threshold; octopuses already over it flash from the step's own increase.
+    #[cfg(feature = "norecurse")]
+    fn flash(&mut self, row: usize, col: usize, trigger: Option<Flash>) -> u32 {
         let mut flashes = 0u32;
-        let mut flashers: Vec<(usize, usize)> = vec![(row,col)];
-        while !flashers.is_empty() {
-            let (row,col) = flashers.pop().unwrap();
-            if self.grid[row][col] <= 9 {
+        let mut flashers: Vec<(usize, usize, Option<Flash>)> = vec![(row, col, trigger)];
+        while let Some((row, col, trigger)) = flashers.pop() {
+            if self.flashed[row][col] || self.grid[row][col] <= self.rules.threshold {
                 continue;
             }
-            self.grid[row][col] = 0;
+            let flash = self.reset(row, col, trigger.as_ref());
             flashes += 1;
 
-            let rmin = if row == 0 { 0 } else { row-1 };
-            let rmax = if row < n-1 { row+1 } else { row };
-            let cmin = if col == 0 { 0 } else { col-1 };
-            let cmax = if col < m-1 { col+1 } else { col };
-            for i in rmin..=rmax {
-                for j in cmin..=cmax {
-                    // only flash once
-                    if self.grid[i][j] != 0 {
-                        self.grid[i][j] += 1;
-                        if self.grid[i][j] > 9 {
-                            flashers.push((i,j));
-                        }
+            for (i, j) in self.neighbors(row, col) {
+                // only flash once
+                if !self.flashed[i][j] {
+                    self.grid[i][j] += 1;
+                    if self.grid[i][j] == self.rules.threshold + 1 {
+                        flashers.push((i, j, Some(flash.clone())));
                     }
                 }
             }
@@ -65,26 +203,21 @@ impl Octopuses {
     }
 
     #[cfg(not(feature = "norecurse"))]
-    fn flash(&mut self, row: usize, col: usize) -> u32 {
-        if self.grid[row][col] <= 9 {
+    fn flash(&mut self, row: usize, col: usize, trigger: Option<Flash>) -> u32 {
+        if self.flashed[row][col] || self.grid[row][col] <= self.rules.threshold {
             return 0;
         }
 
-        // 3: set energy to 0
+        // 3: reset energy
         let mut flashes = 1u32;
-        self.grid[row][col] = 0;
+        let flash = self.reset(row, col, trigger.as_ref());
 
-        let (n, m) = self.grid.size();
-        let rmin = if row == 0 { 0 } else { row-1 };
-        let rmax = if row < n-1 { row+1 } else { row };
-        let cmin = if col == 0 { 0 } else { col-1 };
-        let cmax = if col < m-1 { col+1 } else { col };
-        for i in rmin..=rmax {
-            for j in cmin..=cmax {
-                // only flash once
-                if self.grid[i][j] != 0 {
-                    self.grid[i][j] += 1;
-                    flashes += self.flash(i, j);
+        for (i, j) in self.neighbors(row, col) {
+            // only flash once
+            if !self.flashed[i][j] {
+                self.grid[i][j] += 1;
+                if self.grid[i][j] == self.rules.threshold + 1 {
+                    flashes += self.flash(i, j, Some(flash.clone()));
                 }
             }
         }
@@ -97,15 +230,110 @@ impl Octopuses {
     }
 }
 
-pub fn parse_stdin() -> Octopuses {
-    let mut cells: Vec<u8> = Vec::with_capacity(SIZE*SIZE);
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line doesn't have as many octopuses as the first one.
+    Ragged { line: usize, expected: usize, found: usize },
+    /// A character isn't an energy level from 0 to 9.
+    InvalidEnergy { line: usize, column: usize, found: char },
+    Empty,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ragged { line, expected, found } => write!(f, "line {}: expected {} octopuses, found {}", line, expected, found),
+            Self::InvalidEnergy { line, column, found } => write!(f, "line {}, column {}: invalid energy {:?}", line, column, found),
+            Self::Empty => write!(f, "no octopuses"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+pub fn parse_stdin() -> Result<Octopuses, ParseError> {
+    parse_lines(io::stdin().lock().lines().map_while(Result::ok))
+}
+
+pub fn parse_lines<I>(lines: I) -> Result<Octopuses, ParseError>
+where
+    I: IntoIterator,
+    I::Item: Borrow<str>,
+{
+    let mut cells: Vec<u8> = Vec::new();
+    let mut cols: usize = 0;
+
+    for (l, line) in lines.into_iter().enumerate() {
+        let line = line.borrow().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let row = line.chars().enumerate().map(|(c, ch)| {
+            ch.to_digit(10).map(|d| d as u8).ok_or(ParseError::InvalidEnergy { line: l+1, column: c+1, found: ch })
+        }).collect::<Result<Vec<u8>, _>>()?;
+
+        if cols == 0 {
+            cols = row.len();
+        } else if row.len() != cols {
+            return Err(ParseError::Ragged { line: l+1, expected: cols, found: row.len() });
+        }
+        cells.extend(row);
+    }
+
+    if cells.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    Ok(Octopuses::new(grid::Grid::from_vec(cells, cols), Rules::puzzle()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub const EXAMPLE: &str = "\
+        5483143223
+        2745854711
+        5264556173
+        6141336146
+        6357385478
+        4167524645
+        2176841721
+        6882881134
+        4846848554
+        5283751526";
+
+    #[test]
+    fn puzzle() {
+        let mut octopuses = parse_lines(EXAMPLE.lines()).unwrap();
+        let flashes: u32 = (0..100).map(|_| octopuses.step()).sum();
+        assert_eq!(flashes, 1656);
+    }
+
+    #[test]
+    fn wrap() {
+        let mut octopuses = parse_lines("900\n000\n000".lines()).unwrap();
+        octopuses.set_rules(Rules { wrap: true, ..Rules::puzzle() });
+        assert_eq!(octopuses.neighbors(0, 0).len(), 8);
+        octopuses.step();
+        // Every other octopus got 1 energy from the step, 1 from the flash.
+        assert_eq!(octopuses.energy(), 8 * 2);
+    }
 
-    let stdin = io::stdin();
-    for line in stdin.lock().lines() {
-        cells.extend(line.unwrap().into_bytes().iter().map(|n| n-0x30).collect::<Vec<u8>>());
+    #[test]
+    fn reset_and_threshold() {
+        let mut octopuses = parse_lines("45".lines()).unwrap();
+        octopuses.set_rules(Rules { threshold: 4, reset: 2, neighborhood: Neighborhood::Four, wrap: false });
+        // Both flash: 5 > 4 first, then 4+1+1 = 6 > 4.
+        assert_eq!(octopuses.step(), 2);
+        assert_eq!(octopuses.energy(), 4);
     }
 
-    Octopuses {
-        grid: grid::Grid::from_vec(cells, SIZE)
+    #[test]
+    fn parse() {
+        assert_eq!(parse_lines("12\n34".lines()).unwrap().size(), (2, 2));
+        assert_eq!(parse_lines("12\n3".lines()).err(), Some(ParseError::Ragged { line: 2, expected: 2, found: 1 }));
+        assert_eq!(parse_lines("12\n3/".lines()).err(), Some(ParseError::InvalidEnergy { line: 2, column: 2, found: '/' }));
+        assert_eq!(parse_lines("1a".lines()).err(), Some(ParseError::InvalidEnergy { line: 1, column: 2, found: 'a' }));
+        assert_eq!(parse_lines("\n".lines()).err(), Some(ParseError::Empty));
     }
 }
@@ -0,0 +1,73 @@
+use std::io::{self, Write};
+
+use crate::Octopuses;
+
+pub type Rgb = [u8; 3];
+pub type Image = grid::Grid<Rgb>;
+
+const FLASH: Rgb = [255, 255, 160];
+
+/// Render the octopuses after a step: the ones that flashed are bright yellow, the others are
+/// shaded blue by their energy.
+pub fn render(octopuses: &Octopuses) -> Image {
+    let (n, m) = octopuses.size();
+    let threshold = octopuses.rules().threshold.max(1) as f64;
+
+    let mut image: Image = grid::Grid::init(n, m, [0, 0, 0]);
+    for i in 0..n {
+        for j in 0..m {
+            image[i][j] = if octopuses.flashed(i, j) {
+                FLASH
+            } else {
+                let shade = (octopuses.energy_at(i, j) as f64 / threshold).min(1.0);
+                [(32.0 * shade) as u8, (64.0 + 96.0 * shade) as u8, (96.0 + 159.0 * shade) as u8]
+            };
+        }
+    }
+
+    image
+}
+
+/// Write the image as a binary PPM.
+pub fn write_ppm<W: Write>(image: &Image, out: &mut W) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.cols(), image.rows())?;
+    for pixel in image.iter() {
+        out.write_all(pixel)?;
+    }
+    Ok(())
+}
+
+/// Write the energy of the octopuses to a terminal, on the colors of their image.
+pub fn write_ansi<W: Write>(octopuses: &Octopuses, out: &mut W) -> io::Result<()> {
+    let image = render(octopuses);
+    let (n, m) = octopuses.size();
+    let width = 1 + octopuses.grid.iter().max().map_or(1, |e| e.to_string().len());
+    for i in 0..n {
+        for j in 0..m {
+            let [r, g, b] = image[i][j];
+            write!(out, "\x1b[48;2;{};{};{}m\x1b[30m{:>width$}", r, g, b, octopuses.energy_at(i, j), width = width)?;
+        }
+        writeln!(out, "\x1b[0m")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_lines;
+
+    #[test]
+    fn frame() {
+        let mut octopuses = parse_lines("90".lines()).unwrap();
+        octopuses.step();
+        let image = render(&octopuses);
+        assert_eq!(image[0][0], FLASH);
+        assert_ne!(image[0][1], FLASH);
+
+        let mut out = Vec::new();
+        write_ppm(&image, &mut out).unwrap();
+        assert!(out.starts_with(b"P6\n2 1\n255\n"));
+        assert_eq!(out.len(), 11 + 2*3);
+    }
+}
@@ -0,0 +1,33 @@
+use std::env;
+use std::error::Error;
+use std::io;
+
+use passage_pathing::count::edge_usage;
+use passage_pathing::dot::{write_dot, Highlight};
+use passage_pathing::parse_stdin;
+use passage_pathing::policy::VisitPolicy;
+use passage_pathing::search::shortest_path;
+
+/// Usage: passage_pathing_dot [shortest | usage | usage2]
+///
+/// Highlight the shortest path of part 1, or color passages by how many paths of part 1 (or 2) use them.
+fn main() -> Result<(),Box<dyn Error>> {
+    let mode = env::args().nth(1);
+    let map = parse_stdin()?;
+
+    let mut out = io::stdout().lock();
+    match mode.as_deref() {
+        Some("shortest") => {
+            let path = shortest_path(&map, &VisitPolicy::part1())?.ok_or("no path from start to end")?;
+            write_dot(&map, &Highlight::Path(&path), &mut out)?;
+        },
+        Some(usage @ ("usage" | "usage2")) => {
+            let policy = if usage == "usage2" { VisitPolicy::part2() } else { VisitPolicy::part1() };
+            write_dot(&map, &Highlight::Usage(&edge_usage(&map, &policy)?), &mut out)?;
+        },
+        Some(mode) => return Err(format!("unknown mode {:?}", mode).into()),
+        None => write_dot(&map, &Highlight::Nothing, &mut out)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::env;
+use std::error::Error;
+use std::io::{self, Write};
+
+use passage_pathing::parse_stdin;
+use passage_pathing::paths::Paths;
+use passage_pathing::policy::VisitPolicy;
+
+/// Usage: passage_pathing_paths [revisit]
+///
+/// Print every path, one per line.
+fn main() -> Result<(),Box<dyn Error>> {
+    let policy = match env::args().nth(1).as_deref() {
+        Some("revisit") => VisitPolicy::part2(),
+        _ => VisitPolicy::part1(),
+    };
+    let map = parse_stdin()?;
+
+    let mut out = io::stdout().lock();
+    for path in Paths::new(&map, &policy)? {
+        writeln!(out, "{}", path.to_string(&map))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::env;
+use std::error::Error;
+
+use passage_pathing::parse_stdin;
+use passage_pathing::policy::VisitPolicy;
+use passage_pathing::search::{best_paths, longest_path, shortest_path};
+
+/// Usage: passage_pathing_weighted [K]
+fn main() -> Result<(),Box<dyn Error>> {
+    let k: usize = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 3,
+    };
+    let map = parse_stdin()?;
+    let policy = VisitPolicy::part1();
+
+    match shortest_path(&map, &policy)? {
+        Some(path) => println!("shortest: {} ({})", path.to_string(&map), path.cost),
+        None => println!("no path"),
+    }
+    if let Some(path) = longest_path(&map, &policy)? {
+        println!("longest: {} ({})", path.to_string(&map), path.cost);
+    }
+    for (i, path) in best_paths(&map, &policy, k)?.iter().enumerate() {
+        println!("#{}: {} ({})", i+1, path.to_string(&map), path.cost);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use crate::policy::{Limits, PathError, VisitPolicy};
+use crate::{CaveId, Edge, Map};
+
+struct Counter<'a> {
+    map: &'a Map,
+    limits: Limits,
+    end: CaveId,
+    /// Paths to the end for each cave, by visits. Splitting by cave lets lookups borrow the
+    /// visits instead of building a key.
+    memo: Vec<HashMap<Vec<u32>, u64>>,
+    /// The states in `memo`, in the order they were counted: a state comes after all the states
+    /// it leads to.
+    order: Vec<(CaveId, Vec<u32>)>,
+}
+
+impl<'a> Counter<'a> {
+    /// A counter, and the visits when leaving the start.
+    fn new(map: &'a Map, policy: &VisitPolicy) -> Result<(Self, Vec<u32>), PathError> {
+        let limits = Limits::new(map, policy)?;
+        let start = map.start();
+
+        // Unlimited caves are never counted, so that they don't tell states apart.
+        let mut visits = vec![0; map.len()];
+        if limits.limited(start) {
+            visits[start] = 1;
+        }
+        let memo = vec![HashMap::new(); map.len()];
+        Ok((Counter { map, limits, end: map.end(), memo, order: Vec::new() }, visits))
+    }
+
+    /// The visits once the cave is visited.
+    fn visit(&self, visits: &[u32], cave: CaveId) -> Vec<u32> {
+        let mut visits = visits.to_vec();
+        if self.limits.limited(cave) {
+            visits[cave] += 1;
+        }
+        visits
+    }
+
+    /// Paths to the end from a cave, given the visits to each cave so far.
+    fn count(&mut self, cave: CaveId, visits: &mut Vec<u32>) -> u64 {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(&paths) = self.memo[cave].get(visits.as_slice()) {
+            return paths;
+        }
+
+        let mut paths = 0;
+        for &Edge { to: next, .. } in &self.map[cave].edges {
+            if !self.limits.can_visit(visits, next) {
+                continue;
+            }
+            if self.limits.limited(next) {
+                visits[next] += 1;
+                paths += self.count(next, visits);
+                visits[next] -= 1;
+            } else {
+                paths += self.count(next, visits);
+            }
+        }
+
+        self.memo[cave].insert(visits.clone(), paths);
+        self.order.push((cave, visits.clone()));
+        paths
+    }
+}
+
+/// Count the paths from start to end allowed by the policy.
+pub fn count_paths(map: &Map, policy: &VisitPolicy) -> Result<u64, PathError> {
+    let (mut counter, mut visits) = Counter::new(map, policy)?;
+    Ok(counter.count(map.start(), &mut visits))
+}
+
+/// How many of the paths allowed by the policy go through each passage, by pair of caves
+/// (lowest id first). A path going through a passage twice counts twice.
+// Each step from a state to the next one is taken by as many paths as there are ways to reach
+// the first state, times paths from the second one to the end.
+pub fn edge_usage(map: &Map, policy: &VisitPolicy) -> Result<HashMap<(CaveId, CaveId), u64>, PathError> {
+    let (mut counter, mut visits) = Counter::new(map, policy)?;
+    let start = map.start();
+    counter.count(start, &mut visits);
+
+    let mut usage = HashMap::new();
+    // Ways to reach each state from the start, split by cave like the memo.
+    let mut ways: Vec<HashMap<Vec<u32>, u64>> = vec![HashMap::new(); map.len()];
+    ways[start].insert(visits, 1);
+    for (cave, visits) in counter.order.iter().rev() {
+        let cave = *cave;
+        let w = ways[cave].get(visits).copied().unwrap_or(0);
+        for &Edge { to: next, .. } in &map[cave].edges {
+            if !counter.limits.can_visit(visits, next) {
+                continue;
+            }
+            let next_visits = counter.visit(visits, next);
+            let paths = if next == counter.end { 1 } else { counter.memo[next][&next_visits] };
+            *usage.entry((cave.min(next), cave.max(next))).or_insert(0) += w * paths;
+            if next != counter.end {
+                *ways[next].entry(next_visits).or_insert(0) += w;
+            }
+        }
+    }
+    usage.retain(|_, &mut u| u > 0);
+
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_lines;
+    use crate::paths::Paths;
+    use crate::tests::{LARGE, MEDIUM, SMALL};
+
+    #[test]
+    fn puzzle() {
+        let counts: Vec<(u64, u64)> = [SMALL, MEDIUM, LARGE].iter().map(|input| {
+            let map = parse_lines(input.lines()).unwrap();
+            (count_paths(&map, &VisitPolicy::part1()).unwrap(), count_paths(&map, &VisitPolicy::part2()).unwrap())
+        }).collect();
+        assert_eq!(counts, vec![(10, 36), (19, 103), (226, 3509)]);
+    }
+
+    #[test]
+    fn usage() {
+        let map = parse_lines(SMALL.lines()).unwrap();
+        for policy in [VisitPolicy::part1(), VisitPolicy::part2()] {
+            let mut expected = HashMap::new();
+            for path in Paths::new(&map, &policy).unwrap() {
+                for pair in path.caves.windows(2) {
+                    *expected.entry((pair[0].min(pair[1]), pair[0].max(pair[1]))).or_insert(0) += 1;
+                }
+            }
+            assert_eq!(edge_usage(&map, &policy).unwrap(), expected);
+        }
+    }
+}
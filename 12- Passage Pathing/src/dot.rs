@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::{CaveId, Map, Path};
+
+/// What to emphasize in the graph.
+pub enum Highlight<'a> {
+    Nothing,
+    /// The caves and passages of a path.
+    Path(&'a Path),
+    /// Passages colored from blue to red by how many paths use them, as given by
+    /// [`edge_usage`](crate::count::edge_usage).
+    Usage(&'a HashMap<(CaveId, CaveId), u64>),
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Write the caves as an undirected Graphviz graph. Big caves are boxes, small ones are ellipses,
+/// and start and end are double circles. Passages are labelled with their weight, unless it's 1.
+pub fn write_dot<W: Write>(map: &Map, highlight: &Highlight, out: &mut W) -> io::Result<()> {
+    let (start, end) = (map.id("start"), map.id("end"));
+    let on_path = |cave: CaveId| matches!(highlight, Highlight::Path(path) if path.caves.contains(&cave));
+    let max_usage = match highlight {
+        Highlight::Usage(usage) => usage.values().copied().max().unwrap_or(0).max(1),
+        _ => 1,
+    };
+
+    writeln!(out, "graph caves {{")?;
+    for (id, cave) in map.caves().iter().enumerate() {
+        let mut attributes = vec![format!("label={}", quote(&cave.name))];
+        if Some(id) == start || Some(id) == end {
+            attributes.push("shape=doublecircle".to_owned());
+            attributes.push("style=filled".to_owned());
+            attributes.push(format!("fillcolor={}", if Some(id) == start { "palegreen" } else { "salmon" }));
+        } else if cave.small {
+            attributes.push("shape=ellipse".to_owned());
+        } else {
+            attributes.push("shape=box".to_owned());
+            attributes.push("style=filled".to_owned());
+            attributes.push("fillcolor=lightblue".to_owned());
+        }
+        if on_path(id) {
+            attributes.push("color=red".to_owned());
+            attributes.push("penwidth=2".to_owned());
+        }
+        writeln!(out, "    {} [{}];", id, attributes.join(", "))?;
+    }
+
+    for (a, cave) in map.caves().iter().enumerate() {
+        for edge in cave.edges.iter().filter(|e| e.to >= a) {
+            let b = edge.to;
+            let mut label = if edge.weight != 1 { edge.weight.to_string() } else { String::new() };
+            let mut attributes = Vec::new();
+            match highlight {
+                Highlight::Nothing => (),
+                Highlight::Path(path) => {
+                    if path.caves.windows(2).any(|p| (p[0], p[1]) == (a, b) || (p[0], p[1]) == (b, a)) {
+                        attributes.push("color=red".to_owned());
+                        attributes.push("penwidth=3".to_owned());
+                    }
+                },
+                Highlight::Usage(usage) => {
+                    let used = usage.get(&(a, b)).copied().unwrap_or(0);
+                    let fraction = used as f64 / max_usage as f64;
+                    // Hue from blue (0.667) to red (0).
+                    attributes.push(format!("color=\"{:.3} 1.000 1.000\"", 0.667 * (1.0 - fraction)));
+                    attributes.push(format!("penwidth={:.1}", 1.0 + 4.0 * fraction));
+                    label = if label.is_empty() { format!("×{}", used) } else { format!("{} ×{}", label, used) };
+                },
+            }
+            if !label.is_empty() {
+                attributes.push(format!("label={}", quote(&label)));
+            }
+            if attributes.is_empty() {
+                writeln!(out, "    {} -- {};", a, b)?;
+            } else {
+                writeln!(out, "    {} -- {} [{}];", a, b, attributes.join(", "))?;
+            }
+        }
+    }
+    writeln!(out, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count::edge_usage;
+    use crate::parse_lines;
+    use crate::policy::VisitPolicy;
+    use crate::search::shortest_path;
+
+    fn dot(map: &Map, highlight: &Highlight) -> String {
+        let mut out = Vec::new();
+        write_dot(map, highlight, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn graph() {
+        let map = parse_lines("start-A\nA-b:4\nA-end".lines()).unwrap();
+        assert_eq!(dot(&map, &Highlight::Nothing), "\
+graph caves {
+    0 [label=\"start\", shape=doublecircle, style=filled, fillcolor=palegreen];
+    1 [label=\"A\", shape=box, style=filled, fillcolor=lightblue];
+    2 [label=\"b\", shape=ellipse];
+    3 [label=\"end\", shape=doublecircle, style=filled, fillcolor=salmon];
+    0 -- 1;
+    1 -- 2 [label=\"4\"];
+    1 -- 3;
+}
+");
+
+        let path = shortest_path(&map, &VisitPolicy::part1()).unwrap().unwrap();
+        let graph = dot(&map, &Highlight::Path(&path));
+        assert!(graph.contains("    1 -- 3 [color=red, penwidth=3];"));
+        assert!(graph.contains("    1 -- 2 [label=\"4\"];"));
+
+        let usage = edge_usage(&map, &VisitPolicy::part1()).unwrap();
+        let graph = dot(&map, &Highlight::Usage(&usage));
+        // Both paths go through start-A and A-end, only one through A-b (twice).
+        assert!(graph.contains("    0 -- 1 [color=\"0.000 1.000 1.000\", penwidth=5.0, label=\"×2\"];"));
+        assert!(graph.contains("    1 -- 2 [color=\"0.000 1.000 1.000\", penwidth=5.0, label=\"4 ×2\"];"));
+    }
+}
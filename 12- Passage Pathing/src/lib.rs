@@ -1,9 +1,28 @@
-use std::collections::{HashMap, HashSet};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::io::{self, BufRead};
+use std::ops::Index;
+
+pub mod count;
+pub mod dot;
+pub mod paths;
+pub mod policy;
+pub mod search;
+
+pub type CaveId = usize;
+
+/// A passage to another cave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub to: CaveId,
+    pub weight: u64,
+}
 
 pub struct Cave {
     pub name: String,
-    pub edges: HashSet<String>,
+    pub edges: Vec<Edge>,
     pub small: bool,
 }
 
@@ -11,45 +30,194 @@ impl Cave {
     fn new(name: &str) -> Cave {
         Cave {
             name: name.to_owned(),
-            edges: HashSet::new(),
+            edges: Vec::new(),
             small: name.chars().all(char::is_lowercase),
         }
     }
 }
 
-#[derive(Clone)]
+/// The caves, with their names interned as ids.
+#[derive(Default)]
+pub struct Map {
+    caves: Vec<Cave>,
+    ids: HashMap<String, CaveId>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(&self, name: &str) -> Option<CaveId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn caves(&self) -> &[Cave] {
+        &self.caves
+    }
+
+    pub fn len(&self) -> usize {
+        self.caves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.caves.is_empty()
+    }
+
+    fn intern(&mut self, name: &str) -> CaveId {
+        if let Some(id) = self.id(name) {
+            return id;
+        }
+        self.caves.push(Cave::new(name));
+        self.ids.insert(name.to_owned(), self.caves.len() - 1);
+        self.caves.len() - 1
+    }
+
+    /// Add a passage between two caves, adding the caves if needed. Connecting them again
+    /// changes the weight of the passage.
+    pub fn connect(&mut self, a: &str, b: &str, weight: u64) {
+        let (a, b) = (self.intern(a), self.intern(b));
+        for (from, to) in [(a, b), (b, a)] {
+            let edges = &mut self.caves[from].edges;
+            match edges.iter_mut().find(|e| e.to == to) {
+                Some(edge) => edge.weight = weight,
+                None => edges.push(Edge { to, weight }),
+            }
+            if a == b {
+                break;
+            }
+        }
+    }
+
+    /// Weight of the passage between two caves.
+    pub fn weight(&self, a: CaveId, b: CaveId) -> Option<u64> {
+        self.caves[a].edges.iter().find(|e| e.to == b).map(|e| e.weight)
+    }
+
+    /// The start cave. Panics if there is none, which [`parse_lines`] rules out.
+    pub fn start(&self) -> CaveId {
+        self.id("start").expect("no start cave")
+    }
+
+    /// The end cave. Panics if there is none, which [`parse_lines`] rules out.
+    pub fn end(&self) -> CaveId {
+        self.id("end").expect("no end cave")
+    }
+}
+
+impl Index<CaveId> for Map {
+    type Output = Cave;
+
+    fn index(&self, id: CaveId) -> &Cave {
+        &self.caves[id]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Path {
-    pub caves: Vec<String>,
-    pub small_caves: HashSet<String>,
-    pub small_cave2: Option<String>,
+    pub caves: Vec<CaveId>,
+    /// Total weight of the passages taken.
+    pub cost: u64,
 }
 
 impl Path {
-    pub fn new(start: &Cave) -> Path {
-        Path {
-            caves: vec![start.name.clone()],
-            small_caves: if start.small { HashSet::from([start.name.clone()]) }
-                else { HashSet::new() },
-            small_cave2: None,
+    pub fn names<'a>(&self, map: &'a Map) -> Vec<&'a str> {
+        self.caves.iter().map(|&c| map[c].name.as_str()).collect()
+    }
+
+    /// The names of the caves, separated by commas.
+    pub fn to_string(&self, map: &Map) -> String {
+        self.names(map).join(",")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line isn't two caves separated by '-'.
+    Passage { line: usize },
+    /// A passage has a weight that isn't a number.
+    InvalidWeight { line: usize, weight: String },
+    /// No passage leads to the start or end cave.
+    Missing(&'static str),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Passage { line } => write!(f, "line {}: expected two caves separated by '-'", line),
+            Self::InvalidWeight { line, weight } => write!(f, "line {}: invalid weight {:?}", line, weight),
+            Self::Missing(cave) => write!(f, "no {} cave", cave),
         }
     }
 }
 
-pub type Map = HashMap<String, Cave>;
+impl Error for ParseError {}
 
-pub fn parse_stdin() -> Map {
-    let mut caves = Map::new();
+pub fn parse_stdin() -> Result<Map, ParseError> {
+    parse_lines(io::stdin().lock().lines().map_while(Result::ok))
+}
 
-    for line in io::stdin().lock().lines() {
-        let line = line.unwrap();
-        let names: Vec<&str> = line.split('-').collect();
+/// Parse passages as `a-B`, with an optional weight as `a-B:7`. Passages weigh 1 by default.
+/// There must be a start and an end cave.
+pub fn parse_lines<I>(lines: I) -> Result<Map, ParseError>
+where
+    I: IntoIterator,
+    I::Item: Borrow<str>,
+{
+    let mut map = Map::new();
 
-        let cave0 = caves.entry(names[0].to_owned()).or_insert_with(|| Cave::new(names[0]));
-        cave0.edges.insert(names[1].to_owned());
+    for (l, line) in lines.into_iter().enumerate() {
+        let line = line.borrow().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (passage, weight) = match line.split_once(':') {
+            Some((passage, weight)) => {
+                let weight = weight.trim().parse().map_err(|_| ParseError::InvalidWeight { line: l+1, weight: weight.to_owned() })?;
+                (passage, weight)
+            },
+            None => (line, 1),
+        };
+        let (a, b) = passage.split_once('-').ok_or(ParseError::Passage { line: l+1 })?;
+        let (a, b) = (a.trim(), b.trim());
+        if a.is_empty() || b.is_empty() {
+            return Err(ParseError::Passage { line: l+1 });
+        }
+        map.connect(a, b, weight);
+    }
 
-        let cave1 = caves.entry(names[1].to_owned()).or_insert_with(|| Cave::new(names[1]));
-        cave1.edges.insert(names[0].to_owned());
+    for cave in ["start", "end"] {
+        if map.id(cave).is_none() {
+            return Err(ParseError::Missing(cave));
+        }
     }
+    Ok(map)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    pub const SMALL: &str = "start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end";
+    pub const MEDIUM: &str = "dc-end\nHN-start\nstart-kj\ndc-start\ndc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\nkj-dc";
+    pub const LARGE: &str = "fs-end\nhe-DX\nfs-he\nstart-DX\npj-DX\nend-zg\nzg-sl\nzg-pj\npj-he\nRW-he\nfs-DX\npj-RW\nzg-RW\nstart-pj\nhe-WI\nzg-he\npj-fs\nstart-RW";
 
-    caves
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let map = parse_lines(SMALL.lines()).unwrap();
+        assert_eq!(map.len(), 6);
+        let a = map.id("A").unwrap();
+        assert!(!map[a].small);
+        assert_eq!(map[a].edges.len(), 4);
+        let path = Path { caves: vec![map.start(), a, map.end()], cost: 2 };
+        assert_eq!(path.to_string(&map), "start,A,end");
+
+        let map = parse_lines("start-A:7\nA-end".lines()).unwrap();
+        assert_eq!(map.weight(map.start(), map.id("A").unwrap()), Some(7));
+        assert_eq!(map.weight(map.end(), map.id("A").unwrap()), Some(1));
+        assert_eq!(parse_lines("start-A:x".lines()).err(), Some(ParseError::InvalidWeight { line: 1, weight: "x".to_owned() }));
+        assert_eq!(parse_lines("start\n".lines()).err(), Some(ParseError::Passage { line: 1 }));
+        assert_eq!(parse_lines("start-A".lines()).err(), Some(ParseError::Missing("end")));
+        assert_eq!(parse_lines("".lines()).err(), Some(ParseError::Missing("start")));
+    }
 }
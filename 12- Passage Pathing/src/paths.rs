@@ -0,0 +1,91 @@
+use crate::policy::{Limits, PathError, VisitPolicy};
+use crate::{CaveId, Edge, Map, Path};
+
+/// Lazily walks the paths counted by [`count_paths`](crate::count::count_paths), depth first.
+pub struct Paths<'a> {
+    map: &'a Map,
+    limits: Limits,
+    end: CaveId,
+    /// The current path, with the index of the next edge to follow from each cave.
+    stack: Vec<(CaveId, usize)>,
+    visits: Vec<u32>,
+    /// Cost of the current path.
+    cost: u64,
+}
+
+impl<'a> Paths<'a> {
+    pub fn new(map: &'a Map, policy: &VisitPolicy) -> Result<Self, PathError> {
+        let limits = Limits::new(map, policy)?;
+        let (start, end) = (map.start(), map.end());
+        let mut visits = vec![0; map.len()];
+        visits[start] = 1;
+        Ok(Paths { map, limits, end, stack: vec![(start, 0)], visits, cost: 0 })
+    }
+
+    fn enter(&mut self, edge: Edge) {
+        self.stack.push((edge.to, 0));
+        self.visits[edge.to] += 1;
+        self.cost += edge.weight;
+    }
+
+    fn leave(&mut self) {
+        if let Some((cave, _)) = self.stack.pop() {
+            self.visits[cave] -= 1;
+            if let Some(&(previous, _)) = self.stack.last() {
+                self.cost -= self.map.weight(previous, cave).unwrap();
+            }
+        }
+    }
+}
+
+impl Iterator for Paths<'_> {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Path> {
+        loop {
+            let (cave, edge) = *self.stack.last()?;
+            match self.map[cave].edges.get(edge) {
+                Some(&next) => {
+                    self.stack.last_mut().unwrap().1 += 1;
+                    if !self.limits.can_visit(&self.visits, next.to) {
+                        continue;
+                    }
+                    self.enter(next);
+                    if next.to == self.end {
+                        let path = Path { caves: self.stack.iter().map(|&(c, _)| c).collect(), cost: self.cost };
+                        self.leave();
+                        return Some(path);
+                    }
+                },
+                None => self.leave(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::count::count_paths;
+    use crate::parse_lines;
+    use crate::tests::{LARGE, SMALL};
+
+    #[test]
+    fn lazy() {
+        let map = parse_lines(SMALL.lines()).unwrap();
+        let paths: HashSet<String> = Paths::new(&map, &VisitPolicy::part1()).unwrap().map(|p| p.to_string(&map)).collect();
+        assert_eq!(paths.len(), 10);
+        assert!(paths.contains("start,A,b,A,c,A,end"));
+
+        let map = parse_lines("start-A:2\nA-b:3\nA-end:5".lines()).unwrap();
+        let costs: Vec<u64> = Paths::new(&map, &VisitPolicy::part1()).unwrap().map(|p| p.cost).collect();
+        assert_eq!(costs, vec![13, 7]);
+
+        let map = parse_lines(LARGE.lines()).unwrap();
+        let policy = VisitPolicy::part2();
+        assert_eq!(Paths::new(&map, &policy).unwrap().count() as u64, count_paths(&map, &policy).unwrap());
+        assert_eq!(Paths::new(&map, &policy).unwrap().take(5).count(), 5);
+    }
+}
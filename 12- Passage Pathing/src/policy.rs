@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{CaveId, Edge, Map};
+
+/// Extra visits for small caves: up to `caves` of them can each be visited up to `visits` times.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bonus {
+    pub caves: u32,
+    pub visits: u32,
+}
+
+/// How many times each cave can be visited by a path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VisitPolicy {
+    /// Visits allowed to each small cave.
+    pub small: u32,
+    /// Visits allowed to each big cave, or `None` for no limit.
+    pub big: Option<u32>,
+    /// Visits allowed to given caves, overriding their class. Bonuses don't apply to them.
+    pub caves: HashMap<String, u32>,
+    pub bonuses: Vec<Bonus>,
+}
+
+impl VisitPolicy {
+    /// Small caves are visited at most once.
+    pub fn part1() -> Self {
+        VisitPolicy {
+            small: 1,
+            big: None,
+            caves: HashMap::from([("start".to_owned(), 1)]),
+            bonuses: Vec::new(),
+        }
+    }
+
+    /// A single small cave can be visited twice.
+    pub fn part2() -> Self {
+        VisitPolicy {
+            bonuses: vec![Bonus { caves: 1, visits: 2 }],
+            ..Self::part1()
+        }
+    }
+}
+
+impl Default for VisitPolicy {
+    fn default() -> Self {
+        Self::part1()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PathError {
+    /// Two adjacent caves can both be visited any number of times, so that paths can go back and
+    /// forth between them forever.
+    Infinite(String, String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Infinite(a, b) => write!(f, "infinitely many paths through {}-{}", a, b),
+        }
+    }
+}
+
+impl Error for PathError {}
+
+/// A policy applied to the caves of a map.
+pub(crate) struct Limits {
+    /// Visits allowed to each cave, before bonuses.
+    limits: Vec<Option<u32>>,
+    /// Whether bonuses apply to each cave.
+    bonus: Vec<bool>,
+    /// Visits allowed by each bonus slot, highest first.
+    slots: Vec<u32>,
+    /// Visits allowed to the caves bonuses apply to, before bonuses.
+    small: u32,
+}
+
+impl Limits {
+    pub fn new(map: &Map, policy: &VisitPolicy) -> Result<Self, PathError> {
+        let mut limits = Vec::with_capacity(map.len());
+        let mut bonus = Vec::with_capacity(map.len());
+        for cave in map.caves() {
+            let (limit, b) = match policy.caves.get(&cave.name) {
+                Some(&limit) => (Some(limit), false),
+                None if cave.small => (Some(policy.small), true),
+                None => (policy.big, false),
+            };
+            limits.push(limit);
+            bonus.push(b);
+        }
+
+        for (a, cave) in map.caves().iter().enumerate() {
+            for &Edge { to: b, .. } in &cave.edges {
+                if limits[a].is_none() && limits[b].is_none() {
+                    return Err(PathError::Infinite(cave.name.clone(), map[b].name.clone()));
+                }
+            }
+        }
+
+        let mut slots: Vec<u32> = policy.bonuses.iter()
+            .flat_map(|b| std::iter::repeat_n(b.visits, b.caves as usize))
+            .collect();
+        slots.sort_unstable_by(|a, b| b.cmp(a));
+
+        Ok(Limits { limits, bonus, slots, small: policy.small })
+    }
+
+    /// Whether visits to the cave are counted at all.
+    pub fn limited(&self, cave: CaveId) -> bool {
+        self.limits[cave].is_some()
+    }
+
+    /// Visits left to the cave before bonuses, if it is limited.
+    pub fn left(&self, visits: &[u32], cave: CaveId) -> Option<u32> {
+        self.limits[cave].map(|limit| limit.saturating_sub(visits[cave]))
+    }
+
+    /// At most how many more visits bonuses allow, to all caves together.
+    pub fn bonus_left(&self, visits: &[u32]) -> u32 {
+        let total: u32 = self.slots.iter().map(|s| s.saturating_sub(self.small)).sum();
+        let used: u32 = (0..visits.len())
+            .filter(|&c| self.bonus[c])
+            .map(|c| visits[c].saturating_sub(self.small))
+            .sum();
+        total.saturating_sub(used)
+    }
+
+    /// Whether bonuses apply to the cave.
+    pub fn bonus(&self, cave: CaveId) -> bool {
+        self.bonus[cave]
+    }
+
+    /// Whether the cave can be visited once more, given the visits so far.
+    pub fn can_visit(&self, visits: &[u32], cave: CaveId) -> bool {
+        let v = visits[cave] + 1;
+        match self.limits[cave] {
+            None => true,
+            Some(limit) if v <= limit => true,
+            Some(_) if !self.bonus[cave] => false,
+            Some(_) => {
+                // Each cave over its limit needs a bonus slot of its own: match them greedily,
+                // from the most visited one.
+                let mut over: Vec<u32> = (0..visits.len())
+                    .filter(|&c| self.bonus[c])
+                    .map(|c| (c, if c == cave { v } else { visits[c] }))
+                    .filter(|&(c, v)| Some(v) > self.limits[c])
+                    .map(|(_, v)| v)
+                    .collect();
+                over.sort_unstable_by(|a, b| b.cmp(a));
+                over.len() <= self.slots.len() && over.iter().zip(&self.slots).all(|(v, s)| v <= s)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count::count_paths;
+    use crate::parse_lines;
+    use crate::tests::SMALL;
+
+    #[test]
+    fn bonuses() {
+        let map = parse_lines(SMALL.lines()).unwrap();
+        let count = |bonuses| count_paths(&map, &VisitPolicy { bonuses, ..VisitPolicy::part1() }).unwrap();
+        let (one, two) = (count(vec![]), count(vec![Bonus { caves: 1, visits: 2 }]));
+        assert_eq!((one, two), (10, 36));
+        // Any two small caves twice, or one of them three times.
+        let twice = count(vec![Bonus { caves: 2, visits: 2 }]);
+        let thrice = count(vec![Bonus { caves: 1, visits: 3 }]);
+        assert!(twice > two && thrice > two);
+        assert!(count(vec![Bonus { caves: 2, visits: 2 }, Bonus { caves: 1, visits: 3 }]) > twice.max(thrice));
+    }
+
+    #[test]
+    fn per_cave() {
+        let map = parse_lines(SMALL.lines()).unwrap();
+        let mut policy = VisitPolicy::part1();
+        policy.caves.insert("c".to_owned(), 0);
+        // Half of the paths go through c.
+        assert_eq!(count_paths(&map, &policy).unwrap(), 5);
+        // start,A,end start,A,b,end start,b,A,end start,b,end
+        policy.big = Some(1);
+        assert_eq!(count_paths(&map, &policy).unwrap(), 4);
+    }
+
+    #[test]
+    fn infinite() {
+        let map = parse_lines("start-A\nA-B\nB-end".lines()).unwrap();
+        assert_eq!(count_paths(&map, &VisitPolicy::part1()), Err(PathError::Infinite("A".to_owned(), "B".to_owned())));
+        let policy = VisitPolicy { big: Some(2), ..VisitPolicy::part1() };
+        // start,A,B,end start,A,B,A,B,end
+        assert_eq!(count_paths(&map, &policy).unwrap(), 2);
+    }
+}
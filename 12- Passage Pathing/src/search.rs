@@ -0,0 +1,199 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::policy::{Limits, PathError, VisitPolicy};
+use crate::{CaveId, Map, Path};
+
+/// The cheapest path from start to end that avoids the caves the policy doesn't let a path visit
+/// even once. A cheapest path never needs to visit a cave twice, so the other limits don't matter;
+/// each cave is checked on its own though, so bonus visits aren't shared out between caves.
+pub fn shortest_path(map: &Map, policy: &VisitPolicy) -> Result<Option<Path>, PathError> {
+    let limits = Limits::new(map, policy)?;
+    let (start, end) = (map.start(), map.end());
+    let mut visits = vec![0; map.len()];
+    visits[start] = 1;
+    let mut costs: Vec<Option<u64>> = vec![None; map.len()];
+    let mut previous: Vec<Option<CaveId>> = vec![None; map.len()];
+    let mut queue = BinaryHeap::from([Reverse((0, start))]);
+    costs[start] = Some(0);
+
+    while let Some(Reverse((cost, cave))) = queue.pop() {
+        if costs[cave].is_some_and(|c| c < cost) {
+            continue;
+        }
+        if cave == end {
+            let mut caves = vec![end];
+            while let Some(p) = previous[*caves.last().unwrap()] {
+                caves.push(p);
+            }
+            caves.reverse();
+            return Ok(Some(Path { caves, cost }));
+        }
+        for edge in &map[cave].edges {
+            if edge.to == start || !limits.can_visit(&visits, edge.to) {
+                continue;
+            }
+            let next = cost + edge.weight;
+            if costs[edge.to].is_none_or(|c| next < c) {
+                costs[edge.to] = Some(next);
+                previous[edge.to] = Some(cave);
+                queue.push(Reverse((next, edge.to)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+struct Longest<'a> {
+    map: &'a Map,
+    limits: Limits,
+    end: CaveId,
+    /// Heaviest passage of each cave.
+    heaviest: Vec<u64>,
+    /// Heaviest passage of the caves bonuses apply to.
+    heaviest_bonus: u64,
+    path: Vec<CaveId>,
+    visits: Vec<u32>,
+    best: Option<Path>,
+}
+
+impl Longest<'_> {
+    /// At most how much the rest of a path can cost. Unlimited caves are never adjacent, so every
+    /// passage left touches a visit to a limited cave: the current one, or one of the visits left,
+    /// which each come with at most two passages.
+    fn bound(&self, cave: CaveId) -> u64 {
+        let left: u64 = (0..self.map.len())
+            .filter_map(|c| self.limits.left(&self.visits, c).map(|left| left as u64 * 2 * self.heaviest[c]))
+            .sum();
+        let bonus = self.limits.bonus_left(&self.visits) as u64 * 2 * self.heaviest_bonus;
+        self.heaviest[cave] + left + bonus
+    }
+
+    fn search(&mut self, cave: CaveId, cost: u64) {
+        if cave == self.end {
+            if self.best.as_ref().is_none_or(|best| cost > best.cost) {
+                self.best = Some(Path { caves: self.path.clone(), cost });
+            }
+            return;
+        }
+        if self.best.as_ref().is_some_and(|best| cost + self.bound(cave) <= best.cost) {
+            return;
+        }
+
+        for edge in &self.map[cave].edges {
+            if !self.limits.can_visit(&self.visits, edge.to) {
+                continue;
+            }
+            self.visits[edge.to] += 1;
+            self.path.push(edge.to);
+            self.search(edge.to, cost + edge.weight);
+            self.path.pop();
+            self.visits[edge.to] -= 1;
+        }
+    }
+}
+
+/// The most expensive path from start to end allowed by the policy, found by branch and bound.
+pub fn longest_path(map: &Map, policy: &VisitPolicy) -> Result<Option<Path>, PathError> {
+    let limits = Limits::new(map, policy)?;
+    let heaviest: Vec<u64> = map.caves().iter()
+        .map(|cave| cave.edges.iter().map(|e| e.weight).max().unwrap_or(0))
+        .collect();
+    let heaviest_bonus = (0..map.len()).filter(|&c| limits.bonus(c)).map(|c| heaviest[c]).max().unwrap_or(0);
+
+    let start = map.start();
+    let mut visits = vec![0; map.len()];
+    visits[start] = 1;
+    let mut longest = Longest { map, limits, end: map.end(), heaviest, heaviest_bonus, path: vec![start], visits, best: None };
+    longest.search(start, 0);
+    Ok(longest.best)
+}
+
+/// The `k` cheapest paths from start to end allowed by the policy, cheapest first.
+// Best-first search over partial paths: as weights aren't negative, complete paths come out of
+// the queue in order of cost.
+pub fn best_paths(map: &Map, policy: &VisitPolicy, k: usize) -> Result<Vec<Path>, PathError> {
+    let limits = Limits::new(map, policy)?;
+    let (start, end) = (map.start(), map.end());
+    let mut queue = BinaryHeap::from([Reverse((0, vec![start]))]);
+    let mut paths = Vec::new();
+
+    while let Some(Reverse((cost, caves))) = queue.pop() {
+        if paths.len() == k {
+            break;
+        }
+        let cave = *caves.last().unwrap();
+        if cave == end {
+            paths.push(Path { caves, cost });
+            continue;
+        }
+
+        let mut visits = vec![0; map.len()];
+        for &c in &caves {
+            visits[c] += 1;
+        }
+        for edge in &map[cave].edges {
+            if limits.can_visit(&visits, edge.to) {
+                let mut next = caves.clone();
+                next.push(edge.to);
+                queue.push(Reverse((cost + edge.weight, next)));
+            }
+        }
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count::count_paths;
+    use crate::parse_lines;
+    use crate::paths::Paths;
+    use crate::tests::SMALL;
+
+    const WEIGHTED: &str = "start-A:2\nstart-b:9\nA-c:1\nA-b:3\nb-d:4\nA-end:20\nb-end:1";
+
+    #[test]
+    fn shortest() {
+        let map = parse_lines(WEIGHTED.lines()).unwrap();
+        let path = shortest_path(&map, &VisitPolicy::part1()).unwrap().unwrap();
+        assert_eq!((path.to_string(&map), path.cost), ("start,A,b,end".to_owned(), 6));
+
+        // Forbidden caves are avoided, as by the other searches.
+        let mut policy = VisitPolicy::part1();
+        policy.caves.insert("b".to_owned(), 0);
+        let path = shortest_path(&map, &policy).unwrap().unwrap();
+        assert_eq!((path.to_string(&map), path.cost), ("start,A,end".to_owned(), 22));
+        assert_eq!(best_paths(&map, &policy, 1).unwrap(), vec![path]);
+        policy.caves.insert("A".to_owned(), 0);
+        assert_eq!(shortest_path(&map, &policy).unwrap(), None);
+    }
+
+    #[test]
+    fn longest() {
+        let map = parse_lines(WEIGHTED.lines()).unwrap();
+        for policy in [VisitPolicy::part1(), VisitPolicy::part2()] {
+            let expected = Paths::new(&map, &policy).unwrap().map(|p| p.cost).max();
+            assert_eq!(longest_path(&map, &policy).unwrap().map(|p| p.cost), expected);
+        }
+        let path = longest_path(&map, &VisitPolicy::part1()).unwrap().unwrap();
+        assert_eq!((path.to_string(&map), path.cost), ("start,b,A,c,A,end".to_owned(), 9 + 3 + 1 + 1 + 20));
+    }
+
+    #[test]
+    fn best() {
+        let map = parse_lines(WEIGHTED.lines()).unwrap();
+        let policy = VisitPolicy::part2();
+        let mut costs: Vec<u64> = Paths::new(&map, &policy).unwrap().map(|p| p.cost).collect();
+        costs.sort_unstable();
+        let best = best_paths(&map, &policy, 5).unwrap();
+        assert_eq!(best.iter().map(|p| p.cost).collect::<Vec<_>>(), costs[..5]);
+        assert_eq!(best[0].to_string(&map), "start,A,b,end");
+
+        // Unweighted, every path comes out.
+        let map = parse_lines(SMALL.lines()).unwrap();
+        assert_eq!(best_paths(&map, &policy, usize::MAX).unwrap().len() as u64, count_paths(&map, &policy).unwrap());
+    }
+}
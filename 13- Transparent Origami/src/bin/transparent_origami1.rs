@@ -1,9 +1,13 @@
+use std::error::Error;
+
 use transparent_origami::parse_stdin;
 
-fn main() {
+fn main() -> Result<(),Box<dyn Error>> {
     let (mut grid, folds) = parse_stdin();
 
     println!("grid has {} dots", grid.count());
-    grid.fold(&folds[0]);
+    grid.fold(&folds[0])?;
     println!("grid has {} dots after 1st fold", grid.count());
+
+    Ok(())
 }
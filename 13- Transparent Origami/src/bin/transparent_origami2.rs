@@ -1,12 +1,18 @@
+use std::error::Error;
+
+use transparent_origami::ocr;
 use transparent_origami::parse_stdin;
 
-fn main() {
+fn main() -> Result<(),Box<dyn Error>> {
     let (mut grid, folds) = parse_stdin();
 
     println!("grid has {} dots", grid.count());
     for (i, fold) in folds.iter().enumerate() {
-        grid.fold(fold);
+        grid.fold(fold)?;
         println!("grid has {} dots after fold {}", grid.count(), i+1);
     }
     println!("{}", grid);
+    println!("code: {}", ocr::read(&grid)?);
+
+    Ok(())
 }
@@ -0,0 +1,23 @@
+use std::env;
+use std::error::Error;
+use std::io;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use transparent_origami::generate::generate;
+use transparent_origami::ocr::{LARGE, SMALL};
+
+/// Usage: transparent_origami_generate TEXT [FOLDS] [SEED] [large]
+fn main() -> Result<(),Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let text = args.next().ok_or("missing text")?;
+    let folds: usize = args.next().map_or(Ok(12), |arg| arg.parse())?;
+    let seed: u64 = args.next().map_or(Ok(2021), |arg| arg.parse())?;
+    let font = if args.next().as_deref() == Some("large") { LARGE } else { SMALL };
+
+    let puzzle = generate(&text, &font, folds, &mut StdRng::seed_from_u64(seed))?;
+    puzzle.write_input(&mut io::stdout().lock())?;
+
+    Ok(())
+}
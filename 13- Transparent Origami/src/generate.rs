@@ -0,0 +1,149 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use rand::Rng;
+
+use crate::ocr::Font;
+use crate::{Fold, Grid};
+
+/// An unfolded sheet, and the folds that reveal its message.
+pub struct Puzzle {
+    pub grid: Grid,
+    pub folds: Vec<Fold>,
+}
+
+impl Puzzle {
+    /// Write the puzzle in the input format: dots as `x,y`, a blank line, then the folds.
+    pub fn write_input<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut dots: Vec<&(usize, usize)> = self.grid.dots().collect();
+        dots.sort_unstable_by_key(|&&(x, y)| (y, x));
+        for (x, y) in dots {
+            writeln!(out, "{},{}", x, y)?;
+        }
+        writeln!(out)?;
+        for fold in &self.folds {
+            writeln!(out, "{}", fold)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GenerateError {
+    /// The font has no glyph for the character.
+    Letter(char),
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Letter(c) => write!(f, "no glyph for {:?}", c),
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+/// The text drawn in the font, as read by [`ocr::read`](crate::ocr::read).
+pub fn render(text: &str, font: &Font) -> Result<Grid, GenerateError> {
+    let mut dots = Vec::new();
+    for (i, letter) in text.chars().enumerate() {
+        let glyph = font.glyph(letter).ok_or(GenerateError::Letter(letter))?;
+        let left = i * (font.width + font.spacing);
+        for (y, row) in glyph.iter().enumerate() {
+            dots.extend(row.chars().enumerate().filter(|&(_, c)| c == '#').map(|(x, _)| (left + x, y)));
+        }
+    }
+
+    let mut grid = Grid::from_dots(dots);
+    let letters = text.chars().count();
+    grid.resize(font.height, (letters * (font.width + font.spacing)).saturating_sub(font.spacing));
+    Ok(grid)
+}
+
+/// Hide the text behind `folds` random folds.
+// Unfold the message one fold at a time: the sheet grows by a random flap of at most its own
+// size, so that folding it back never shifts the dots. Each dot goes to its place, its mirror
+// image on the flap, or both.
+pub fn generate<R: Rng>(text: &str, font: &Font, folds: usize, rng: &mut R) -> Result<Puzzle, GenerateError> {
+    let mut grid = render(text, font)?;
+    let mut unfolds = Vec::with_capacity(folds);
+
+    for _ in 0..folds {
+        let axis = if rng.gen_bool(0.5) { 'x' } else { 'y' };
+        let coord = if axis == 'x' { grid.cols() } else { grid.rows() };
+        let flap = rng.gen_range((coord / 2).max(1)..=coord.max(1));
+
+        let mut dots = Vec::with_capacity(grid.count() * 2);
+        for &(x, y) in grid.dots() {
+            let p = if axis == 'x' { x } else { y };
+            let mirror = 2*coord - p;
+            let (keep, copy) = if mirror > coord + flap {
+                (true, false)
+            } else {
+                match rng.gen_range(0..3) {
+                    0 => (true, false),
+                    1 => (false, true),
+                    _ => (true, true),
+                }
+            };
+            if keep {
+                dots.push((x, y));
+            }
+            if copy {
+                dots.push(if axis == 'x' { (mirror, y) } else { (x, mirror) });
+            }
+        }
+
+        let (rows, cols) = if axis == 'x' {
+            (grid.rows(), coord + 1 + flap)
+        } else {
+            (coord + 1 + flap, grid.cols())
+        };
+        grid = Grid::from_dots(dots);
+        grid.resize(rows, cols);
+        unfolds.push(Fold { axis, coord });
+    }
+
+    unfolds.reverse();
+    Ok(Puzzle { grid, folds: unfolds })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::ocr::{self, LARGE, SMALL};
+    use crate::parse_lines;
+
+    fn round_trip(text: &str, font: &Font, folds: usize, seed: u64) {
+        let puzzle = generate(text, font, folds, &mut StdRng::seed_from_u64(seed)).unwrap();
+        let mut input = Vec::new();
+        puzzle.write_input(&mut input).unwrap();
+        let input = String::from_utf8(input).unwrap();
+
+        let (mut grid, folds) = parse_lines(input.lines());
+        assert_eq!(folds, puzzle.folds);
+        for fold in &folds {
+            grid.fold(fold).unwrap();
+        }
+        assert_eq!(ocr::read(&grid), Ok(text.to_owned()), "{}", grid);
+    }
+
+    #[test]
+    fn round_trips() {
+        for seed in 0..20 {
+            round_trip("HELLO", &SMALL, 8, seed);
+            round_trip("ZEBRA", &LARGE, 4, seed);
+        }
+        round_trip("CAB", &SMALL, 0, 0);
+    }
+
+    #[test]
+    fn unknown_letter() {
+        assert_eq!(render("HI!", &SMALL).err(), Some(GenerateError::Letter('I')));
+    }
+}
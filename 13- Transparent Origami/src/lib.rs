@@ -1,78 +1,128 @@
+use std::borrow::Borrow;
 use std::cmp;
-use std::default;
+use std::collections::HashSet;
+use std::error::Error;
 use std::fmt;
 use std::io::{self, BufRead};
 
-use grid;
-use regex;
+pub mod generate;
+pub mod ocr;
 
-pub enum Cell {
-    Blank,
-    Dot,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fold {
+    pub axis: char,
+    pub coord: usize,
 }
 
-impl default::Default for Cell {
-    fn default() -> Self {
-        Cell::Blank
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "fold along {}={}", self.axis, self.coord)
     }
 }
 
-pub struct Fold {
-    pub axis: char,
-    pub coord: usize,
+#[derive(Debug, PartialEq, Eq)]
+pub enum FoldError {
+    Axis(char),
+    /// The fold line isn't on the sheet.
+    OutOfBounds { fold: Fold, size: usize },
+    /// A dot is on the fold line.
+    DotOnLine { x: usize, y: usize },
 }
 
+impl fmt::Display for FoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Axis(axis) => write!(f, "invalid fold axis: {}", axis),
+            Self::OutOfBounds { fold, size } => write!(f, "{}: out of the sheet, which is {} wide", fold, size),
+            Self::DotOnLine { x, y } => write!(f, "dot {},{} is on the fold line", x, y),
+        }
+    }
+}
+
+impl Error for FoldError {}
+
+/// A transparent sheet with dots at (x, y) positions.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Grid {
-    cells: grid::Grid<Cell>,
+    dots: HashSet<(usize, usize)>,
     rows: usize,
     cols: usize,
 }
 
 impl Grid {
-    fn new(rows: usize, cols: usize) -> Self {
-        Grid {
-            cells: grid::Grid::new(rows, cols),
-            rows,
-            cols,
-        }
+    /// A sheet just big enough for the dots.
+    pub fn from_dots<I: IntoIterator<Item = (usize, usize)>>(dots: I) -> Self {
+        let dots: HashSet<(usize, usize)> = dots.into_iter().collect();
+        let rows = dots.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+        let cols = dots.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        Grid { dots, rows, cols }
+    }
+
+    /// A grid drawn with `#` for dots, as displayed.
+    pub fn from_art(art: &str) -> Self {
+        let lines: Vec<&str> = art.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        let mut grid = Grid::from_dots(lines.iter().enumerate().flat_map(|(y, line)| {
+            line.chars().enumerate().filter(|&(_, c)| c == '#').map(move |(x, _)| (x, y))
+        }));
+        grid.rows = lines.len();
+        grid.cols = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        grid
+    }
+
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn cols(&self) -> usize { self.cols }
+
+    /// Grow the sheet, keeping the dots where they are.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        self.rows = cmp::max(self.rows, rows);
+        self.cols = cmp::max(self.cols, cols);
+    }
+
+    pub fn dots(&self) -> impl Iterator<Item = &(usize, usize)> {
+        self.dots.iter()
+    }
+
+    pub fn is_dot(&self, x: usize, y: usize) -> bool {
+        self.dots.contains(&(x, y))
     }
 
     pub fn count(&self) -> usize {
-        let mut count = 0;
-        for y in 0..self.rows {
-            for x in 0..self.cols {
-                if matches!(self.cells[y][x], Cell::Dot) {
-                    count += 1;
-                }
-            }
-        }
-        count
+        self.dots.len()
     }
 
-    pub fn fold(&mut self, fold: &Fold) {
-        match fold.axis {
-            'x' => {
-                for x in 1..(self.cols - fold.coord) {
-                    for y in 0..self.rows {
-                        if matches!(self.cells[y][fold.coord + x], Cell::Dot) {
-                            self.cells[y][fold.coord - x] = Cell::Dot;
-                        }
-                    }
-                }
-                self.cols = fold.coord;
-            },
-            'y' => {
-                for y in 1..(self.rows - fold.coord) {
-                    for x in 0..self.cols {
-                        if matches!(self.cells[fold.coord + y][x], Cell::Dot) {
-                            self.cells[fold.coord - y][x] = Cell::Dot;
-                        }
-                    }
-                }
-                self.rows = fold.coord;
-            },
-            _ => { eprintln!("invalid fold axis: {}", fold.axis); },
+    /// Fold the sheet. When the part folded over is the larger one, it sticks out past the edge of
+    /// the other: the result is shifted so that coordinates start at 0 again.
+    pub fn fold(&mut self, fold: &Fold) -> Result<(), FoldError> {
+        let size = match fold.axis {
+            'x' => self.cols,
+            'y' => self.rows,
+            axis => return Err(FoldError::Axis(axis)),
         };
+        if fold.coord >= size {
+            return Err(FoldError::OutOfBounds { fold: *fold, size });
+        }
+        let along = |&(x, y): &(usize, usize)| if fold.axis == 'x' { x } else { y };
+        if let Some(&(x, y)) = self.dots.iter().find(|d| along(d) == fold.coord) {
+            return Err(FoldError::DotOnLine { x, y });
+        }
+
+        let c = fold.coord;
+        let (before, after) = (c, size - c - 1);
+        let shift = after.saturating_sub(before);
+        let moved = |p: usize| if p < c { p + shift } else { shift + c - (p - c) };
+        self.dots = self.dots.iter().map(|&(x, y)| match fold.axis {
+            'x' => (moved(x), y),
+            _ => (x, moved(y)),
+        }).collect();
+
+        let size = cmp::max(before, after);
+        if fold.axis == 'x' {
+            self.cols = size;
+        } else {
+            self.rows = size;
+        }
+        Ok(())
     }
 }
 
@@ -80,12 +130,9 @@ impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for y in 0..self.rows {
             for x in 0..self.cols {
-                write!(f, "{}", match self.cells[y][x] {
-                    Cell::Dot => "#",
-                    Cell::Blank => ".",
-                })?;
+                write!(f, "{}", if self.is_dot(x, y) { "#" } else { "." })?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         fmt::Result::Ok(())
     }
@@ -97,37 +144,34 @@ enum ParseMode {
 }
 
 pub fn parse_stdin() -> (Grid, Vec<Fold>) {
-    let mut grid: Option<Grid> = None;
+    parse_lines(io::stdin().lock().lines().map_while(Result::ok))
+}
+
+pub fn parse_lines<I>(lines: I) -> (Grid, Vec<Fold>)
+where
+    I: IntoIterator,
+    I::Item: Borrow<str>,
+{
     let mut folds: Vec<Fold> = Vec::new();
 
     let fold_re = regex::Regex::new(r"fold along (?P<axis>[xy])=(?P<coord>[0-9]+)").unwrap();
 
-    let mut rows = 1;
-    let mut cols = 1;
     let mut dots: Vec<(usize,usize)> = Vec::new();
     let mut mode: ParseMode = ParseMode::Dots;
-    for line in io::stdin().lock().lines() {
-        let line = line.unwrap();
+    for line in lines {
+        let line = line.borrow();
         if line.trim().is_empty() {
-            // done parsing dots, create the grid
-            println!("grid size: ({}, {})", rows, cols);
-            grid = Some(Grid::new(rows, cols));
-            for dot in &dots {
-                grid.as_mut().map(|g| g.cells[dot.0][dot.1] = Cell::Dot);
-            }
-            dots.clear();
+            // done parsing dots
             mode = ParseMode::Folds;
             continue;
         }
         match mode {
             ParseMode::Dots => {
-                let coords: Vec<usize> = line.split(',').map(|n| n.parse::<usize>().unwrap()).collect();
-                rows = cmp::max(rows, coords[1] + 1);
-                cols = cmp::max(cols, coords[0] + 1);
-                dots.push((coords[1], coords[0]));
+                let coords: Vec<usize> = line.split(',').map(|n| n.trim().parse::<usize>().unwrap()).collect();
+                dots.push((coords[0], coords[1]));
             },
             ParseMode::Folds => {
-                let captures = fold_re.captures(&line).unwrap();
+                let captures = fold_re.captures(line).unwrap();
                 folds.push(Fold {
                     axis: captures[1].chars().next().unwrap(),
                     coord: captures[2].parse::<usize>().unwrap(),
@@ -136,5 +180,55 @@ pub fn parse_stdin() -> (Grid, Vec<Fold>) {
         }
     }
 
-    (grid.unwrap(), folds)
+    let mut grid = Grid::from_dots(dots);
+    // The sheet is at least big enough to be folded in half along the first folds.
+    for axis in ['x', 'y'] {
+        if let Some(fold) = folds.iter().find(|f| f.axis == axis) {
+            let size = 2*fold.coord + 1;
+            if axis == 'x' {
+                grid.resize(0, size);
+            } else {
+                grid.resize(size, 0);
+            }
+        }
+    }
+
+    (grid, folds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "6,10\n0,14\n9,10\n0,3\n10,4\n4,11\n6,0\n6,12\n4,1\n0,13\n10,12\n3,4\n3,0\n8,4\n1,10\n2,14\n8,10\n9,0\n\nfold along y=7\nfold along x=5";
+
+    #[test]
+    fn puzzle() {
+        let (mut grid, folds) = parse_lines(EXAMPLE.lines());
+        assert_eq!(grid.count(), 18);
+        grid.fold(&folds[0]).unwrap();
+        assert_eq!(grid.count(), 17);
+        grid.fold(&folds[1]).unwrap();
+        assert_eq!(grid.to_string(), "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....\n");
+    }
+
+    #[test]
+    fn asymmetric() {
+        // Folding left of the middle: the right part sticks out and gets shifted back.
+        let mut grid = Grid::from_art("#.#..#");
+        grid.fold(&Fold { axis: 'x', coord: 1 }).unwrap();
+        assert_eq!(grid.to_string(), "#..#\n");
+
+        let mut grid = Grid::from_art("#\n.\n.\n#\n.");
+        grid.fold(&Fold { axis: 'y', coord: 2 }).unwrap();
+        assert_eq!(grid.to_string(), "#\n#\n");
+    }
+
+    #[test]
+    fn invalid() {
+        let mut grid = Grid::from_art("#.#..#");
+        assert_eq!(grid.fold(&Fold { axis: 'x', coord: 6 }), Err(FoldError::OutOfBounds { fold: Fold { axis: 'x', coord: 6 }, size: 6 }));
+        assert_eq!(grid.fold(&Fold { axis: 'x', coord: 2 }), Err(FoldError::DotOnLine { x: 2, y: 0 }));
+        assert_eq!(grid.fold(&Fold { axis: 'z', coord: 1 }), Err(FoldError::Axis('z')));
+    }
 }
@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Grid;
+
+/// Capital letters drawn with dots, one glyph every `width + spacing` columns.
+pub struct Font {
+    pub width: usize,
+    pub height: usize,
+    pub spacing: usize,
+    /// Rows of each glyph, with `#` for dots.
+    pub glyphs: &'static [(char, &'static [&'static str])],
+}
+
+impl Font {
+    /// The glyph of a letter, if the font has it.
+    pub fn glyph(&self, letter: char) -> Option<&'static [&'static str]> {
+        self.glyphs.iter().find(|&&(c, _)| c == letter).map(|&(_, rows)| rows)
+    }
+}
+
+/// The 4×6 font of most puzzles.
+pub const SMALL: Font = Font {
+    width: 4,
+    height: 6,
+    spacing: 1,
+    glyphs: &[
+        ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+        ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+        ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+        ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+        ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+        ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+        ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+        ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+        ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+        ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+        ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+        ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+        ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+        ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+    ],
+};
+
+/// The 6×10 font of larger puzzles.
+pub const LARGE: Font = Font {
+    width: 6,
+    height: 10,
+    spacing: 2,
+    glyphs: &[
+        ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+        ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+        ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+        ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+        ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+        ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+        ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+        ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+        ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+        ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+        ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+    ],
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OcrError {
+    /// No font is as tall as the dots.
+    Height(usize),
+    /// The glyph at the given position (counting from 0) isn't a letter of the font.
+    Unrecognized { index: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Height(height) => write!(f, "no font is {} dots tall", height),
+            Self::Unrecognized { index, glyph } => write!(f, "unrecognized glyph #{}:\n{}", index+1, glyph),
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// Read the letters of the grid with the font matching its height. Blank rows around the letters
+/// are ignored.
+pub fn read(grid: &Grid) -> Result<String, OcrError> {
+    let top = (0..grid.rows()).find(|&y| (0..grid.cols()).any(|x| grid.is_dot(x, y)));
+    let top = match top {
+        Some(top) => top,
+        None => return Ok(String::new()),
+    };
+    let bottom = (0..grid.rows()).rev().find(|&y| (0..grid.cols()).any(|x| grid.is_dot(x, y))).unwrap();
+
+    let height = bottom - top + 1;
+    let font = [SMALL, LARGE].into_iter().find(|f| f.height == height).ok_or(OcrError::Height(height))?;
+    read_with(grid, &font, top)
+}
+
+/// Read the letters of the grid with the given font, starting at row `top`.
+pub fn read_with(grid: &Grid, font: &Font, top: usize) -> Result<String, OcrError> {
+    let mut text = String::new();
+    for (index, left) in (0..grid.cols()).step_by(font.width + font.spacing).enumerate() {
+        let rows: Vec<String> = (top..top + font.height)
+            .map(|y| (left..left + font.width).map(|x| if grid.is_dot(x, y) { '#' } else { '.' }).collect())
+            .collect();
+        if rows.iter().all(|r| !r.contains('#')) {
+            continue;
+        }
+        let letter = font.glyphs.iter()
+            .find(|(_, glyph)| glyph.iter().zip(&rows).all(|(a, b)| a == b))
+            .map(|&(c, _)| c)
+            .ok_or_else(|| OcrError::Unrecognized { index, glyph: rows.join("\n") })?;
+        text.push(letter);
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(font: &Font, text: &str) -> Grid {
+        let rows: Vec<String> = (0..font.height).map(|y| {
+            text.chars().map(|c| font.glyph(c).unwrap()[y]).collect::<Vec<_>>().join(&".".repeat(font.spacing))
+        }).collect();
+        Grid::from_art(&rows.join("\n"))
+    }
+
+    #[test]
+    fn small() {
+        let grid = render(&SMALL, "ABCEFGHJKLOPRSUZ");
+        assert_eq!(read(&grid), Ok("ABCEFGHJKLOPRSUZ".to_owned()));
+    }
+
+    #[test]
+    fn large() {
+        let grid = render(&LARGE, "ABCEFGHJKLNPRXZ");
+        assert_eq!(read(&grid), Ok("ABCEFGHJKLNPRXZ".to_owned()));
+    }
+
+    #[test]
+    fn errors() {
+        let grid = Grid::from_art("####.#..#\n#....#..#\n###..####\n#....#..#\n#....#..#\n####.#..#");
+        assert_eq!(read(&grid), Ok("EH".to_owned()));
+        let grid = Grid::from_art("####.#..#\n#....#..#\n###..##.#\n#....#..#\n#....#..#\n####.#..#");
+        assert!(matches!(read(&grid), Err(OcrError::Unrecognized { index: 1, .. })));
+        let grid = Grid::from_art("#\n#\n#");
+        assert_eq!(read(&grid), Err(OcrError::Height(3)));
+    }
+}
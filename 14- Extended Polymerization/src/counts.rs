@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{compute_distrib, grow_polymer_distrib, Rules};
+
+/// How many times each element appears in a polymer. Counts are `u128`, which is enough for
+/// about 120 steps.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElementCounts(pub BTreeMap<char, u128>);
+
+impl ElementCounts {
+    fn add(&mut self, other: &ElementCounts) {
+        for (&element, &count) in &other.0 {
+            let total = self.0.entry(element).or_insert(0);
+            *total = total.checked_add(count).expect("element count overflows u128");
+        }
+    }
+
+    pub fn get(&self, element: char) -> u128 {
+        self.0.get(&element).copied().unwrap_or(0)
+    }
+
+    /// Length of the polymer.
+    pub fn total(&self) -> u128 {
+        self.0.values().try_fold(0u128, |total, &c| total.checked_add(c)).expect("polymer length overflows u128")
+    }
+
+    /// The most common element and its count. Ties go to the first element in alphabetical order.
+    pub fn most_common(&self) -> Option<(char, u128)> {
+        self.0.iter().map(|(&e, &c)| (e, c)).rev().max_by_key(|&(_, c)| c)
+    }
+
+    /// The least common element and its count. Ties go to the first element in alphabetical order.
+    pub fn least_common(&self) -> Option<(char, u128)> {
+        self.0.iter().map(|(&e, &c)| (e, c)).min_by_key(|&(_, c)| c)
+    }
+
+    /// The count of the most common element minus the one of the least common element.
+    pub fn spread(&self) -> u128 {
+        match (self.most_common(), self.least_common()) {
+            (Some((_, most)), Some((_, least))) => most - least,
+            _ => 0,
+        }
+    }
+}
+
+/// Grows polymers by counts, remembering the elements inserted between each pair for each number
+/// of steps. Queries with the same rules share their work.
+pub struct Polymerizer<'a> {
+    rules: &'a Rules,
+    memo: HashMap<((char, char), usize), ElementCounts>,
+}
+
+impl<'a> Polymerizer<'a> {
+    pub fn new(rules: &'a Rules) -> Self {
+        Polymerizer { rules, memo: HashMap::new() }
+    }
+
+    /// Elements inserted between the two elements of a pair after the given number of steps.
+    fn inserted(&mut self, (l, r): (char, char), steps: usize) -> ElementCounts {
+        let m = match self.rules.get(&(l, r)) {
+            Some(&m) if steps > 0 => m,
+            _ => return ElementCounts::default(),
+        };
+        if let Some(counts) = self.memo.get(&((l, r), steps)) {
+            return counts.clone();
+        }
+
+        let mut counts = ElementCounts(BTreeMap::from([(m, 1)]));
+        counts.add(&self.inserted((l, m), steps - 1));
+        counts.add(&self.inserted((m, r), steps - 1));
+        self.memo.insert(((l, r), steps), counts.clone());
+        counts
+    }
+
+    pub fn element_counts(&mut self, template: &str, steps: usize) -> ElementCounts {
+        let mut counts = ElementCounts::default();
+        for element in template.chars() {
+            counts.add(&ElementCounts(BTreeMap::from([(element, 1)])));
+        }
+        for pair in template.chars().zip(template.chars().skip(1)) {
+            counts.add(&self.inserted(pair, steps));
+        }
+        counts
+    }
+}
+
+/// Element counts of the polymer grown from the template for the given number of steps.
+pub fn element_counts(template: &str, rules: &Rules, steps: usize) -> ElementCounts {
+    Polymerizer::new(rules).element_counts(template, steps)
+}
+
+/// Length of the polymer before the first step, then after each step.
+pub fn length_series(template: &str, rules: &Rules, steps: usize) -> Vec<u128> {
+    let first = template.chars().count() as u128;
+    let mut distrib = compute_distrib(template);
+    let mut lengths = vec![first];
+    for _ in 0..steps {
+        distrib = grow_polymer_distrib(distrib, rules);
+        // Each element but the last starts a pair.
+        let pairs = distrib.values().try_fold(0u128, |total, &c| total.checked_add(c));
+        let length = if first == 0 { Some(0) } else { pairs.and_then(|p| p.checked_add(1)) };
+        lengths.push(length.expect("polymer length overflows u128"));
+    }
+    lengths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grow_polymer, parse_lines};
+
+    const EXAMPLE: &str = "NNCB
+
+        CH -> B
+        HH -> N
+        CB -> H
+        NH -> C
+        HB -> C
+        HC -> B
+        HN -> C
+        NN -> C
+        BH -> H
+        NC -> B
+        NB -> B
+        BN -> B
+        BB -> N
+        BC -> B
+        CC -> N
+        CN -> C";
+
+    #[test]
+    fn puzzle() {
+        let (template, rules) = parse_lines(EXAMPLE.lines());
+        let counts = element_counts(&template, &rules, 10);
+        assert_eq!(counts.most_common(), Some(('B', 1749)));
+        assert_eq!(counts.least_common(), Some(('H', 161)));
+        assert_eq!(counts.get('C'), 298);
+        assert_eq!(counts.total(), 3073);
+
+        let mut polymerizer = Polymerizer::new(&rules);
+        assert_eq!(polymerizer.element_counts(&template, 40).spread(), 2188189693529);
+        assert_eq!(polymerizer.element_counts(&template, 100).total(), 3 * (1 << 100) + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u128")]
+    fn overflow() {
+        let (template, rules) = parse_lines(EXAMPLE.lines());
+        length_series(&template, &rules, 130);
+    }
+
+    #[test]
+    fn lengths() {
+        let (template, rules) = parse_lines(EXAMPLE.lines());
+        let lengths = length_series(&template, &rules, 10);
+        assert_eq!(lengths[..5], [4, 7, 13, 25, 49]);
+        assert_eq!(lengths[10], 3073);
+
+        let mut polymer = template.clone();
+        for _ in 0..5 {
+            polymer = grow_polymer(polymer, &rules);
+        }
+        let counts = element_counts(&template, &rules, 5);
+        assert_eq!(counts.0.iter().map(|(&e, &c)| (e, c as usize)).collect::<Vec<_>>(),
+            "BCHN".chars().map(|e| (e, polymer.matches(e).count())).collect::<Vec<_>>());
+        assert_eq!(counts.total(), lengths[5]);
+    }
+}
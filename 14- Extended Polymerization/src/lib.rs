@@ -1,10 +1,13 @@
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::io::{self, BufRead};
 
 use itertools::Itertools;
 
+pub mod counts;
+
 pub type Rules = HashMap<(char, char), char>;
-pub type Distrib = HashMap<(char, char), usize>;
+pub type Distrib = HashMap<(char, char), u128>;
 
 pub fn grow_polymer(template: String, rules: &Rules) -> String {
     let mut polymer = String::new();
@@ -24,17 +27,23 @@ pub fn compute_distrib(template: &str) -> Distrib {
     let iter_left = template.chars();
     let mut iter_right = template.chars();
     iter_right.next();
-    iter_left.zip(iter_right).counts()
+    iter_left.zip(iter_right).counts().into_iter().map(|(pair, count)| (pair, count as u128)).collect()
+}
+
+/// Count the pair `count` more times. Counts are `u128`, which is enough for about 120 steps.
+fn add_pairs(distrib: &mut Distrib, pair: (char, char), count: u128) {
+    let total = distrib.entry(pair).or_insert(0);
+    *total = total.checked_add(count).expect("pair count overflows u128");
 }
 
 pub fn grow_polymer_distrib(template: Distrib, rules: &Rules) -> Distrib {
     let mut distrib = Distrib::new();
-    for ((l, r), count) in template.iter() {
-        if let Some(m) = rules.get(&(*l, *r)) {
-            *distrib.entry((*l,*m)).or_insert(0) += count;
-            *distrib.entry((*m,*r)).or_insert(0) += count;
+    for (&(l, r), &count) in template.iter() {
+        if let Some(&m) = rules.get(&(l, r)) {
+            add_pairs(&mut distrib, (l, m), count);
+            add_pairs(&mut distrib, (m, r), count);
         } else {
-            *distrib.entry((*l,*r)).or_insert(0) += count;
+            add_pairs(&mut distrib, (l, r), count);
         }
     }
     distrib
@@ -46,19 +55,27 @@ enum ParseMode {
 }
 
 pub fn parse_stdin() -> (String, Rules) {
+    parse_lines(io::stdin().lock().lines().map_while(Result::ok))
+}
+
+pub fn parse_lines<I>(lines: I) -> (String, Rules)
+where
+    I: IntoIterator,
+    I::Item: Borrow<str>,
+{
     let mut template = String::new();
     let mut rules = Rules::new();
 
     let mut mode: ParseMode = ParseMode::Template;
-    for line in io::stdin().lock().lines() {
-        let line = line.unwrap();
-        if line.trim().is_empty() {
+    for line in lines {
+        let line = line.borrow().trim();
+        if line.is_empty() {
             mode = ParseMode::Rules;
             continue;
         }
         match mode {
             ParseMode::Template => {
-                template = line;
+                template = line.to_owned();
             },
             ParseMode::Rules => {
                 let rule: Vec<&str> = line.split(" -> ").collect();